@@ -1,45 +1,490 @@
 const express = require('express');
 const cors = require('cors');
 const bodyParser = require('body-parser');
-const { exec } = require('child_process');
+const { execFile, execFileSync, spawn } = require('child_process');
 const path = require('path');
 const crypto = require('crypto');
+const fs = require('fs');
+const os = require('os');
+const { AbiCoder, Wallet, concat, getBytes, keccak256, toBeHex, toUtf8Bytes, verifyMessage } = require('ethers');
 
 const app = express();
 const PORT = process.env.PORT || 3000;
 
 // Middleware
 app.use(cors());
-app.use(bodyParser.json());
+app.use(bodyParser.json({ limit: '1mb' })); // Event ledgers of long games
+
+// Proof jobs, keyed by job id (events are kept so late subscribers can catch up)
+const proofJobs = new Map();
+const JOB_RETENTION_MS = 10 * 60 * 1000;
+
+// Job ids are UUIDs (crypto.randomUUID), anything else is rejected
+const JOB_ID_PATTERN = /^[0-9a-f-]{36}$/;
+
+// Known prover output lines that mark a proving stage
+const PROOF_STAGES = [
+    { match: 'Game Data:', stage: 'input', message: 'Game data received by the prover' },
+    { match: 'Using cached SP1 keys', stage: 'setup', message: 'Using cached SP1 keys' },
+    { match: 'Setting up SP1 program', stage: 'setup', message: 'Setting up SP1 program...' },
+    { match: 'Generating standard proof', stage: 'prove', message: 'Generating proof, please wait...' },
+    { match: 'Standard proof successfully generated', stage: 'proved', message: 'Proof generated' },
+    { match: 'Standard proof successfully verified', stage: 'verified', message: 'Proof verified' },
+    { match: 'Proof saved to file', stage: 'saved', message: 'Proof saved' }
+];
+
+// Session tickets: seed + expiry + nonce, signed by the service for every game
+const TICKET_TTL_SECONDS = Number(process.env.TICKET_TTL_SECONDS || 2 * 60 * 60);
+const ticketSigner = process.env.TICKET_SIGNER_KEY ? new Wallet(process.env.TICKET_SIGNER_KEY) : null;
+
+// Tickets issued by this service, keyed by nonce (forgotten once expired)
+const issuedTickets = new Map();
+
+function forgetExpiredTickets() {
+    const now = Math.floor(Date.now() / 1000);
+    for (const [nonce, ticket] of issuedTickets) {
+        if (ticket.expiresAt <= now) issuedTickets.delete(nonce);
+    }
+}
+
+// Daily challenges: the seed of the day is keccak256("zklabubu-sp1.daily" || "YYYY-MM-DD" || secret),
+// the secret keeps future days' sequences private
+const DAILY_DOMAIN = 'zklabubu-sp1.daily';
+const DAILY_SECRET = process.env.DAILY_SECRET || '';
+
+// A daily game started before midnight can still be proven for this long after its day ends
+const DAILY_GRACE_SECONDS = Number(process.env.DAILY_GRACE_SECONDS || TICKET_TTL_SECONDS);
+
+function dailySeed(date) {
+    const hash = keccak256(concat([toUtf8Bytes(DAILY_DOMAIN), toUtf8Bytes(date), toUtf8Bytes(DAILY_SECRET)]));
+    return toBeHex(BigInt(hash.slice(0, 18)));
+}
+
+// "YYYY-MM-DD" of a YYYYMMDD challenge date, or null if it isn't a calendar date
+function challengeDateString(challengeDate) {
+    const digits = String(challengeDate).padStart(8, '0');
+    const date = `${digits.slice(0, 4)}-${digits.slice(4, 6)}-${digits.slice(6, 8)}`;
+    const parsed = new Date(`${date}T00:00:00Z`);
+    return !isNaN(parsed) && parsed.toISOString().slice(0, 10) === date ? date : null;
+}
+
+// Digest of a ticket: keccak256(abi.encode(uint64 seed, uint64 expiresAt, bytes32 nonce, uint32 challengeDate))
+function ticketDigest({ seed, expiresAt, nonce, challengeDate }) {
+    return keccak256(AbiCoder.defaultAbiCoder().encode(
+        ['uint64', 'uint64', 'bytes32', 'uint32'],
+        [seed, expiresAt, nonce, challengeDate]
+    ));
+}
+
+// Sign the ticket digest with EIP-191
+async function issueSessionTicket({ daily = false } = {}) {
+    // A daily ticket seeds today's (UTC) challenge, a regular one a random game
+    const date = new Date().toISOString().slice(0, 10);
+    const seed = daily ? dailySeed(date) : toBeHex(BigInt('0x' + crypto.randomBytes(8).toString('hex')));
+    const challengeDate = daily ? Number(date.replace(/-/g, '')) : 0;
+    const expiresAt = Math.floor(Date.now() / 1000) + TICKET_TTL_SECONDS;
+    const nonce = '0x' + crypto.randomBytes(32).toString('hex');
+    
+    const signature = await ticketSigner.signMessage(getBytes(ticketDigest({ seed, expiresAt, nonce, challengeDate })));
+    
+    const ticket = { seed, expiresAt, nonce, challengeDate, signature };
+    forgetExpiredTickets();
+    issuedTickets.set(nonce, ticket);
+    return ticket;
+}
+
+// Check a ticket sent back with a proof request: shape, expiry, signature and that this service issued it
+function validateSessionTicket(ticket) {
+    if (!ticket || typeof ticket !== 'object') return 'Missing session ticket';
+    if (typeof ticket.seed !== 'string' || !/^0x[0-9a-fA-F]{1,16}$/.test(ticket.seed)) return 'Invalid ticket seed';
+    if (!Number.isInteger(ticket.expiresAt)) return 'Invalid ticket expiry';
+    if (typeof ticket.nonce !== 'string' || !/^0x[0-9a-fA-F]{64}$/.test(ticket.nonce)) return 'Invalid ticket nonce';
+    if (ticket.challengeDate !== undefined && !Number.isInteger(ticket.challengeDate)) return 'Invalid ticket challenge date';
+    if (typeof ticket.signature !== 'string' || !/^0x[0-9a-fA-F]{130}$/.test(ticket.signature)) return 'Invalid ticket signature';
+    if (ticket.expiresAt <= Math.floor(Date.now() / 1000)) return 'Session ticket expired';
+    
+    const challengeDate = ticket.challengeDate || 0;
+    let signer;
+    try {
+        signer = verifyMessage(getBytes(ticketDigest({ ...ticket, challengeDate })), ticket.signature);
+    } catch (error) {
+        return 'Invalid ticket signature';
+    }
+    if (signer !== ticketSigner.address) return 'Session ticket not issued by this service';
+    
+    // A daily ticket is valid on its own (UTC) day, plus a grace window for games started before midnight,
+    // and must carry the seed of that day
+    if (challengeDate !== 0) {
+        const date = challengeDateString(challengeDate);
+        if (!date) return 'Invalid ticket challenge date';
+        const dayEnd = Date.parse(`${date}T00:00:00Z`) / 1000 + 24 * 60 * 60;
+        if (Math.floor(Date.now() / 1000) >= dayEnd + DAILY_GRACE_SECONDS) return 'Daily challenge is over';
+        if (BigInt(ticket.seed) !== BigInt(dailySeed(date))) return 'Ticket seed is not the daily seed';
+    }
+    
+    const issued = issuedTickets.get(ticket.nonce);
+    if (!issued || BigInt(issued.seed) !== BigInt(ticket.seed) || issued.expiresAt !== ticket.expiresAt
+        || issued.challengeDate !== challengeDate) {
+        return 'Unknown session ticket';
+    }
+    return null;
+}
+
+// Exit code of the prover when the game's nullifier was already spent
+const EXIT_ALREADY_SUBMITTED = 3;
+
+// Game balance: only the shipped configs are proven, so every committed rulesHash is a known one.
+// Configs are matched by the SHA-256 of their canonical JSON (keys sorted).
+const GAME_CONFIG_PATHS = (process.env.GAME_CONFIGS || path.join(__dirname, '..', 'zklabubu_game', 'www', 'game-config.json'))
+    .split(',')
+    .map(configPath => configPath.trim());
+
+function canonicalJson(value) {
+    if (Array.isArray(value)) return `[${value.map(canonicalJson).join(',')}]`;
+    if (value && typeof value === 'object') {
+        return `{${Object.keys(value).sort().map(key => `${JSON.stringify(key)}:${canonicalJson(value[key])}`).join(',')}}`;
+    }
+    return JSON.stringify(value);
+}
+
+function configHash(config) {
+    return crypto.createHash('sha256').update(canonicalJson(config)).digest('hex');
+}
+
+// Shipped config path by hash (the first one is used when a request has no config)
+const shippedConfigs = new Map(GAME_CONFIG_PATHS.map(configPath =>
+    [configHash(JSON.parse(fs.readFileSync(configPath, 'utf8'))), configPath]));
+
+// Prebuilt nullifier query binary (`cargo build --release --bin nullifier`), run directly per request.
+// It is built into the workspace's target directory, which `cargo metadata` reports.
+function workspaceTargetDir() {
+    try {
+        const metadata = execFileSync('cargo', ['metadata', '--format-version', '1', '--no-deps'], {
+            cwd: path.join(__dirname, '..', 'zklabubu_proof', 'script'),
+            encoding: 'utf8',
+            stdio: ['ignore', 'pipe', 'ignore']
+        });
+        return JSON.parse(metadata).target_directory;
+    } catch (error) {
+        // Without cargo, fall back to the root workspace's target directory
+        return path.join(__dirname, '..', 'target');
+    }
+}
+
+const NULLIFIER_BIN = process.env.NULLIFIER_BIN || path.join(workspaceTargetDir(), 'release', 'nullifier');
+
+// Difficulty presets of the prover's --difficulty
+const DIFFICULTIES = ['easy', 'normal', 'hard', 'nightmare'];
+
+// Create a proof job with a fresh id, forgotten after a while if it is never started
+function createProofJob() {
+    const job = { id: crypto.randomUUID(), events: [], subscribers: new Set(), started: false, finished: false };
+    job.expiry = setTimeout(() => proofJobs.delete(job.id), JOB_RETENTION_MS);
+    proofJobs.set(job.id, job);
+    return job;
+}
+
+// Record an event and push it to all subscribers
+function emitJobEvent(job, event, data) {
+    const entry = { event, data };
+    job.events.push(entry);
+    for (const subscriber of job.subscribers) {
+        writeSseEvent(subscriber, entry);
+    }
+}
+
+// Write one Server-Sent Event
+function writeSseEvent(res, { event, data }) {
+    res.write(`event: ${event}\n`);
+    res.write(`data: ${JSON.stringify(data)}\n\n`);
+}
+
+// Mark a job as finished and forget it after a while
+function finishJob(job, event, data) {
+    emitJobEvent(job, event, data);
+    job.finished = true;
+    for (const subscriber of job.subscribers) {
+        subscriber.end();
+    }
+    job.subscribers.clear();
+    clearTimeout(job.expiry);
+    job.expiry = setTimeout(() => proofJobs.delete(job.id), JOB_RETENTION_MS);
+}
+
+// Parse a JSON file written by the prover (null if missing or invalid)
+function readJsonFile(filePath) {
+    try {
+        return JSON.parse(fs.readFileSync(filePath, 'utf8'));
+    } catch (error) {
+        return null;
+    }
+}
+
+// Classify a line of prover output
+function logLevel(line) {
+    if (/error|failed|panicked/i.test(line)) return 'error';
+    if (/success|verified/i.test(line)) return 'success';
+    return 'info';
+}
+
+// Forward prover output to the job, line by line
+function streamLines(job, stream, onLine) {
+    let buffer = '';
+    stream.on('data', chunk => {
+        buffer += chunk.toString();
+        const lines = buffer.split('\n');
+        buffer = lines.pop();
+        lines.forEach(line => onLine(line));
+    });
+    stream.on('end', () => {
+        if (buffer) onLine(buffer);
+    });
+}
+
+function handleProverLine(job, line) {
+    // Strip terminal colours from the SP1 logger
+    const text = line.replace(/\x1b\[[0-9;]*m/g, '').trimEnd();
+    if (!text) return;
+
+    const stage = PROOF_STAGES.find(s => text.includes(s.match));
+    if (stage) {
+        emitJobEvent(job, 'stage', { stage: stage.stage, level: 'progress', message: stage.message });
+    }
+    emitJobEvent(job, 'log', { level: logLevel(text), message: text });
+}
+
+// Create a proof job, so the client can subscribe to its log before starting it
+app.post('/api/proof-jobs', (req, res) => {
+    res.status(201).json({ jobId: createProofJob().id });
+});
+
+// Subscribe to the live log of a proof job
+app.get('/api/proof-jobs/:jobId/events', (req, res) => {
+    if (!JOB_ID_PATTERN.test(req.params.jobId)) {
+        return res.status(400).json({ success: false, error: 'Invalid job id' });
+    }
+    const job = proofJobs.get(req.params.jobId);
+    if (!job) {
+        return res.status(404).json({ success: false, error: 'Unknown proof job' });
+    }
+
+    res.set({
+        'Content-Type': 'text/event-stream',
+        'Cache-Control': 'no-cache',
+        'Connection': 'keep-alive'
+    });
+    res.flushHeaders();
+
+    // Replay what already happened
+    job.events.forEach(entry => writeSseEvent(res, entry));
+    if (job.finished) {
+        return res.end();
+    }
+
+    job.subscribers.add(res);
+    req.on('close', () => job.subscribers.delete(res));
+});
+
+// Session ticket endpoint (one ticket per game, `{ "daily": true }` for the daily challenge)
+app.post('/api/session-tickets', async (req, res) => {
+    if (!ticketSigner) {
+        return res.status(503).json({ success: false, error: 'Session tickets are not enabled (TICKET_SIGNER_KEY not set)' });
+    }
+    
+    try {
+        res.json(await issueSessionTicket({ daily: Boolean(req.body && req.body.daily) }));
+    } catch (error) {
+        console.error('Session ticket error:', error);
+        res.status(500).json({ success: false, error: 'Could not issue session ticket' });
+    }
+});
 
 // SP1 proof generation endpoint
 app.post('/api/generate-proof', (req, res) => {
     const gameData = req.body;
     console.log('Received game data:', gameData);
     
+    if (gameData.jobId !== undefined && !(typeof gameData.jobId === 'string' && JOB_ID_PATTERN.test(gameData.jobId))) {
+        return res.status(400).json({ success: false, error: 'Invalid job id' });
+    }
+    
+    // Jobs are created by POST /api/proof-jobs and run once
+    const job = gameData.jobId === undefined ? createProofJob() : proofJobs.get(gameData.jobId);
+    if (!job) {
+        return res.status(404).json({ success: false, error: 'Unknown proof job' });
+    }
+    if (job.started) {
+        return res.status(409).json({ success: false, jobId: job.id, error: 'Proof job already started' });
+    }
+    
     // Set the script path
     const scriptPath = path.join(__dirname, '..', 'zklabubu_proof', 'script');
     
-    // Create the SP1 proof command - USING DASH (-)
-    const command = `cd "${scriptPath}" && cargo run --bin prove --release -- --prove` +
-        ` --yellow-eggs ${gameData.yellowEggs || 0}` +
-        ` --blue-eggs ${gameData.blueEggs || 0}` +
-        ` --purple-eggs ${gameData.purpleEggs || 0}` +
-        ` --score ${gameData.score || 0}` +
-        ` --game-time ${gameData.gameTime || 0}` +
-        ` --lives ${gameData.lives || 0}`;
+    // SP1 proof command arguments - USING DASH (-)
+    const args = ['run', '--bin', 'prove', '--release', '--', '--prove',
+        '--yellow-eggs', String(gameData.yellowEggs || 0),
+        '--blue-eggs', String(gameData.blueEggs || 0),
+        '--purple-eggs', String(gameData.purpleEggs || 0),
+        '--score', String(gameData.score || 0),
+        '--game-time', String(gameData.gameTime || 0),
+        '--lives', String(gameData.lives || 0),
+        '--rock-hits', String(gameData.rockHits || 0),
+        '--shield-blocks', String(gameData.shieldBlocks || 0),
+        '--extra-lives', String(gameData.extraLives || 0),
+        // One proof file per job, so concurrent provers never share a file
+        '--proof-out', path.join('proofs', `${job.id}.bin`)];
     
-    console.log('Command to run:', command);
+    // Optional player signature binding the proof to a wallet
+    if (gameData.playerSignature) {
+        if (!/^0x[0-9a-fA-F]{130}$/.test(gameData.playerSignature)) {
+            return res.status(400).json({ success: false, error: 'Invalid player signature' });
+        }
+        args.push('--player-signature', gameData.playerSignature);
+    }
+    
+    // Session ticket, required when the service issues tickets (and only accepted then)
+    if (gameData.sessionTicket && !ticketSigner) {
+        return res.status(400).json({ success: false, error: 'Session tickets are not enabled' });
+    }
+    if (ticketSigner) {
+        const ticketError = validateSessionTicket(gameData.sessionTicket);
+        if (ticketError) {
+            return res.status(400).json({ success: false, error: ticketError });
+        }
+        const { seed, expiresAt, nonce, challengeDate = 0, signature } = gameData.sessionTicket;
+        args.push('--ticket', JSON.stringify({ seed, expiresAt, nonce, challengeDate, signature }));
+        args.push('--ticket-issuer', ticketSigner.address);
+    }
+    
+    // Threshold proofs only reveal whether the score reached the threshold
+    const proofKind = gameData.proofKind || 'full';
+    if (proofKind === 'threshold') {
+        if (!Number.isInteger(gameData.threshold) || gameData.threshold < 0) {
+            return res.status(400).json({ success: false, error: 'Invalid threshold' });
+        }
+        args.push('--kind', 'threshold', '--threshold', String(gameData.threshold));
+    } else if (proofKind === 'private') {
+        args.push('--kind', 'private');
+    } else if (proofKind !== 'full') {
+        return res.status(400).json({ success: false, error: `Unknown proof kind: ${proofKind}` });
+    }
+    
+    // Difficulty preset, committed by the proof
+    const difficulty = gameData.difficulty || 'normal';
+    if (!DIFFICULTIES.includes(difficulty)) {
+        return res.status(400).json({ success: false, error: `Unknown difficulty: ${difficulty}` });
+    }
+    args.push('--difficulty', difficulty);
+    
+    // Event ledger is passed to the prover as a file in a private temp directory
+    let tempDir = null;
+    const writeTempFile = (name, value) => {
+        tempDir = tempDir || fs.mkdtempSync(path.join(os.tmpdir(), 'zklabubu-'));
+        const filePath = path.join(tempDir, `${name}.json`);
+        fs.writeFileSync(filePath, JSON.stringify(value));
+        return filePath;
+    };
+    const removeTempFiles = () => {
+        if (tempDir) fs.rm(tempDir, { recursive: true, force: true }, () => {});
+    };
+    
+    // Event ledger, replayed by the program to prove achievements
+    if (gameData.ledger !== undefined) {
+        if (!Array.isArray(gameData.ledger)) {
+            return res.status(400).json({ success: false, error: 'Invalid event ledger' });
+        }
+        args.push('--ledger', writeTempFile('ledger', gameData.ledger));
+    }
+    
+    // Game balance the game was played with (its hash is committed as rulesHash), must be a shipped one
+    const configPath = gameData.config === undefined ? GAME_CONFIG_PATHS[0] : shippedConfigs.get(configHash(gameData.config));
+    if (!configPath) {
+        removeTempFiles();
+        return res.status(400).json({ success: false, error: 'Unsupported game config' });
+    }
+    args.push('--config', configPath);
+    
+    // The prover writes the secret of a private or threshold proof to a file instead of its log
+    let revealPath = null;
+    if (proofKind === 'private' || proofKind === 'threshold') {
+        revealPath = writeTempFile('reveal', null);
+        args.push('--reveal-out', revealPath);
+    }
+    
+    console.log('Command to run:', `cargo ${args.join(' ')}`);
+    job.started = true;
+    emitJobEvent(job, 'stage', { stage: 'queued', level: 'progress', message: 'Starting SP1 prover...' });
+    
+    // Run the prover and stream its output
+    const prover = spawn('cargo', args, {
+        cwd: scriptPath,
+        env: { ...process.env, RUST_LOG: process.env.RUST_LOG || 'info' }
+    });
+    
+    let stdout = '';
+    let stderr = '';
+    streamLines(job, prover.stdout, line => {
+        stdout += line + '\n';
+        handleProverLine(job, line);
+    });
+    streamLines(job, prover.stderr, line => {
+        stderr += line + '\n';
+        handleProverLine(job, line);
+    });
+    
+    prover.on('error', error => {
+        removeTempFiles();
+        console.error('Proof generation error:', error);
+        finishJob(job, 'failed', { level: 'error', message: `Could not start prover: ${error.message}` });
+        res.status(500).json({
+            success: false,
+            jobId: job.id,
+            error: 'Could not generate proof',
+            details: error.message
+        });
+    });
     
     // Execute the command
-    exec(command, (error, stdout, stderr) => {
+    prover.on('close', code => {
+        const reveal = revealPath ? readJsonFile(revealPath) : null;
+        removeTempFiles();
+        
+        // Already answered by the 'error' handler
+        if (job.finished) return;
+        
         console.log('SP1 output:', stdout);
         if (stderr) console.error('SP1 errors:', stderr);
         
-        if (error) {
-            console.error('Proof generation error:', error);
+        if (code === EXIT_ALREADY_SUBMITTED) {
+            const details = stderr.split('\n').find(line => line.includes('already submitted')) || '';
+            finishJob(job, 'failed', { level: 'error', message: 'This game was already submitted' });
+            return res.status(409).json({
+                success: false,
+                jobId: job.id,
+                error: 'Game already submitted',
+                details: details.replace(/^Error: /, '')
+            });
+        }
+        
+        // Rejected by the plausibility checks of the program
+        const implausible = stderr.split('\n').find(line =>
+            line.includes('implausible game') || line.includes('invalid ledger') || line.includes('invalid game config'));
+        if (code !== 0 && implausible) {
+            finishJob(job, 'failed', { level: 'error', message: implausible.replace(/^Error: /, '') });
+            return res.status(422).json({
+                success: false,
+                jobId: job.id,
+                error: 'Implausible game',
+                details: implausible.replace(/^Error: /, '')
+            });
+        }
+        
+        if (code !== 0) {
+            console.error('Proof generation error: exit code', code);
+            finishJob(job, 'failed', { level: 'error', message: `Proof generation failed (exit code ${code})` });
             return res.status(500).json({
                 success: false,
+                jobId: job.id,
                 error: 'Could not generate proof',
                 details: stderr
             });
@@ -57,16 +502,58 @@ app.post('/api/generate-proof', (req, res) => {
         
         // Using "COMP" prefix for compressed proof
         const randomPart = crypto.randomBytes(4).toString('hex');
-        const proofHash = `0xCOMP${scoreHex}${yellowHex}${blueHex}${purpleHex}${randomPart}`;
+        const proofHash = proofKind === 'threshold'
+            ? `0xTHRS${crypto.randomBytes(8).toString('hex')}` // Don't leak the hidden score
+            : proofKind === 'private'
+                ? `0xPRIV${scoreHex}${randomPart}` // Don't leak the hidden egg counts
+                : `0xCOMP${scoreHex}${yellowHex}${blueHex}${purpleHex}${randomPart}`;
+        
+        finishJob(job, 'done', { level: 'success', message: `SP1 proof generated successfully: ${proofHash}` });
+        
+        // Threshold proofs: the player keeps the salt to open the score commitment later
+        if (proofKind === 'threshold') {
+            return res.json({
+                success: true,
+                jobId: job.id,
+                proofHash: proofHash,
+                proofKind: proofKind,
+                threshold: gameData.threshold,
+                passed: calculatedScore >= gameData.threshold,
+                salt: reveal ? reveal.salt : null,
+                opening: reveal,
+                output: stdout
+            });
+        }
+        
+        // Private proofs: the player keeps the reveal to disclose the egg breakdown later
+        if (proofKind === 'private') {
+            return res.json({
+                success: true,
+                jobId: job.id,
+                proofHash: proofHash,
+                proofKind: proofKind,
+                calculatedScore: calculatedScore,
+                scoreIsValid: scoreValid,
+                reveal: reveal,
+                output: stdout
+            });
+        }
+        
+        // Achievements committed by the proof (full proofs with a ledger)
+        const achievementsLine = stdout.split('\n').find(line => line.startsWith('Achievements JSON: '));
         
         // Return the successful result
         res.json({
             success: true,
+            jobId: job.id,
             proofHash: proofHash,
             proofType: "Compressed (SP1ReduceReceipt)",
+            proofKind: proofKind,
             output: stdout,
             calculatedScore: calculatedScore,
             scoreIsValid: scoreValid,
+            achievements: achievementsLine ? JSON.parse(achievementsLine.slice('Achievements JSON: '.length)) : [],
+            difficulty: difficulty,
             gameData: gameData
         });
         
@@ -74,6 +561,35 @@ app.post('/api/generate-proof', (req, res) => {
     });
 });
 
+// Nullifier query endpoint: was a proof already accepted for this game?
+app.get('/api/nullifiers/:nullifier', (req, res) => {
+    const { nullifier } = req.params;
+    if (!/^0x[0-9a-fA-F]{64}$/.test(nullifier)) {
+        return res.status(400).json({ success: false, error: 'Invalid nullifier' });
+    }
+    
+    // Same working directory as the prover, so both use the same registry by default
+    const scriptPath = path.join(__dirname, '..', 'zklabubu_proof', 'script');
+    execFile(NULLIFIER_BIN, [nullifier], { cwd: scriptPath, timeout: 10000 }, (error, stdout, stderr) => {
+        if (error && error.code === 'ENOENT') {
+            return res.status(503).json({
+                success: false,
+                error: 'Nullifier query binary not built',
+                details: `Run \`cargo build --release --bin nullifier\` or set NULLIFIER_BIN (${NULLIFIER_BIN})`
+            });
+        }
+        if (error) {
+            return res.status(500).json({ success: false, error: 'Could not query nullifier registry', details: stderr || error.message });
+        }
+        
+        try {
+            res.json({ success: true, ...JSON.parse(stdout) });
+        } catch (error) {
+            res.status(500).json({ success: false, error: 'Invalid nullifier registry output', details: stdout });
+        }
+    });
+});
+
 // Health check endpoint
 app.get('/api/health', (req, res) => {
     res.json({
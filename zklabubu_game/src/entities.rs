@@ -1,66 +1,131 @@
 use web_sys::{CanvasRenderingContext2d, HtmlImageElement};
-use crate::utils::random;
+use crate::lanes::LaneLayout;
+use crate::utils::Rng;
+use zklabubu_proof_lib::GameConfig;
 
 // Falling item types
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum FallingItemType {
-    YellowEgg,  // 5 points, 45% probability
-    PinkEgg,    // 10 points, 20% probability
-    PurpleEgg,  // 20 points, 10% probability
-    Rock,        // Obstacle, 25% probability
+    YellowEgg,  // 5 points, 45% probability by default
+    PinkEgg,    // 10 points, 20% probability by default
+    PurpleEgg,  // 20 points, 10% probability by default
+    Rock,        // Obstacle, 25% probability by default
     Shield,      // 10 seconds of protection
     DoublePoints, // 10 seconds of 2x points
     ExtraLife,   // Extra life
     SlowDown,    // Slow down - after 1000 points
 }
 
+// Lane change requested for Labubu
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LaneChange {
+    Step(isize), // Lanes to the right (negative to the left)
+    To(usize),   // A given lane
+}
+
 // Labubu player character
 pub struct Labubu {
     pub x: f64,
     pub y: f64,
     pub width: f64,
     pub height: f64,
-    pub position_index: usize,  // Position 0-4
-    pub positions: Vec<f64>,    // 5 possible X positions
+    pub position_index: usize,  // Lane Labubu is in (or sliding to)
+    pub positions: Vec<f64>,    // X position in every lane
+    pub start_index: usize,     // Lane Labubu starts in
     pub img: HtmlImageElement,
+    slide_from: f64,            // X position the current slide started at
+    slide_progress: f64,        // 0.0 to 1.0 (1.0 when standing in a lane)
+    slide_duration: f64,        // Seconds per lane change (0 moves at once)
+    queued_move: Option<LaneChange>, // Next lane change, held until the slide ends
 }
 
 impl Labubu {
-    pub fn new(canvas_width: f64, canvas_height: f64, img: HtmlImageElement) -> Self {
-        let width = 128.0;
-        let height = 128.0;
+    pub fn new(lanes: &LaneLayout, canvas_height: f64, slide_duration: f64, img: HtmlImageElement) -> Self {
+        let width = lanes.fit(128.0);
+        let height = width;
         
-        // Calculate 5 possible positions
-        let lanes = 5;
-        let lane_width = canvas_width / lanes as f64;
-        let positions = (0..lanes)
-            .map(|i| (i as f64 * lane_width) + (lane_width - width) / 2.0)
+        // One position per lane
+        let positions: Vec<f64> = (0..lanes.count())
+            .map(|lane| lanes.x(lane, width))
             .collect();
+        let start_index = lanes.middle(); // Start in the middle
+        let x = positions[start_index];
         
         Labubu {
-            x: canvas_width / 2.0 - width / 2.0,
+            x,
             y: canvas_height - height - 10.0,
             width,
             height,
-            position_index: 2, // Start in the middle
+            position_index: start_index,
             positions,
+            start_index,
             img,
+            slide_from: x,
+            slide_progress: 1.0,
+            slide_duration,
+            queued_move: None,
         }
     }
     
     // Move left
     pub fn move_left(&mut self) {
-        if self.position_index > 0 {
-            self.position_index -= 1;
-            self.x = self.positions[self.position_index];
-        }
+        self.request_move(LaneChange::Step(-1));
     }
     
     // Move right
     pub fn move_right(&mut self) {
-        if self.position_index < self.positions.len() - 1 {
-            self.position_index += 1;
-            self.x = self.positions[self.position_index];
+        self.request_move(LaneChange::Step(1));
+    }
+    
+    // Move straight to a lane
+    pub fn move_to_lane(&mut self, lane: usize) {
+        self.request_move(LaneChange::To(lane));
+    }
+    
+    // Start a lane change, or buffer it while sliding (the latest request wins)
+    fn request_move(&mut self, change: LaneChange) {
+        if self.slide_progress < 1.0 {
+            self.queued_move = Some(change);
+        } else {
+            self.start_slide(change);
+        }
+    }
+    
+    fn start_slide(&mut self, change: LaneChange) {
+        let target = match change {
+            LaneChange::Step(direction) => self.position_index.checked_add_signed(direction),
+            LaneChange::To(lane) => Some(lane),
+        };
+        let Some(target) = target.filter(|&target| target < self.positions.len() && target != self.position_index) else {
+            return;
+        };
+        
+        self.slide_from = self.x;
+        self.position_index = target;
+        if self.slide_duration > 0.0 {
+            self.slide_progress = 0.0;
+        } else {
+            self.x = self.positions[target];
+        }
+    }
+    
+    // Advance the slide by the frame time (game time, so replays move the same way)
+    pub fn update(&mut self, delta_time: f64) {
+        if self.slide_progress < 1.0 {
+            self.slide_progress = (self.slide_progress + delta_time / self.slide_duration).min(1.0);
+            
+            // Ease in and out
+            let t = self.slide_progress;
+            let eased = t * t * (3.0 - 2.0 * t);
+            let target = self.positions[self.position_index];
+            self.x = self.slide_from + (target - self.slide_from) * eased;
+        }
+        
+        // Start the buffered lane change once the slide is done
+        if self.slide_progress >= 1.0 {
+            if let Some(change) = self.queued_move.take() {
+                self.start_slide(change);
+            }
         }
     }
     
@@ -77,9 +142,11 @@ impl Labubu {
     }
     
     // Reset the labubu (when game restarts)
-    pub fn reset(&mut self, _canvas_width: f64) {
-        self.position_index = 2;
+    pub fn reset(&mut self) {
+        self.position_index = self.start_index;
         self.x = self.positions[self.position_index];
+        self.slide_progress = 1.0;
+        self.queued_move = None;
     }
 }
 
@@ -96,42 +163,39 @@ pub struct FallingItem {
 }
 
 impl FallingItem {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        canvas_width: f64, 
+        rng: &mut Rng,
+        config: &GameConfig,
+        lanes: &LaneLayout,
         speed_multiplier: f64,
         yellow_egg_img: HtmlImageElement,
         pink_egg_img: HtmlImageElement,
         purple_egg_img: HtmlImageElement,
         rock_img: HtmlImageElement,
     ) -> Self {
-        let width = 100.0;
-        let height = 100.0;
+        let width = lanes.fit(100.0);
+        let height = width;
         
-        // Choose a random lane (0-4)
-        let lanes = 5;
-        let lane = (random(0.0, lanes as f64).floor()) as usize;
-        let lane_width = canvas_width / lanes as f64;
-        let x = (lane as f64 * lane_width) + (lane_width - width) / 2.0;
+        // Choose a random lane
+        let lane = lanes.random_lane(rng);
+        let x = lanes.x(lane, width);
         
-        // Determine the item type based on probabilities
-        let rand = random(0.0, 1.0);
-        let (item_type, img) = if rand < 0.45 {
-            // 45% chance for yellow egg
+        // Determine the item type based on the spawn chances (percent)
+        let chances = &config.spawn_chances;
+        let rand = rng.range(0.0, 100.0);
+        let (item_type, img) = if rand < chances.yellow_egg as f64 {
             (FallingItemType::YellowEgg, yellow_egg_img)
-        } else if rand < 0.65 {
-            // 20% chance for pink egg
+        } else if rand < (chances.yellow_egg + chances.pink_egg) as f64 {
             (FallingItemType::PinkEgg, pink_egg_img)
-        } else if rand < 0.75 {
-            // 10% chance for purple egg
+        } else if rand < (chances.yellow_egg + chances.pink_egg + chances.purple_egg) as f64 {
             (FallingItemType::PurpleEgg, purple_egg_img)
         } else {
-            // 25% chance for rock
             (FallingItemType::Rock, rock_img)
         };
         
         // Base speed + difficulty multiplier
-        let base_speed = 200.0;
-        let speed = base_speed * speed_multiplier;
+        let speed = config.base_speed as f64 * speed_multiplier;
         
         FallingItem {
             x,
@@ -0,0 +1,59 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Document, Event, Window};
+
+// Browser event callback kept alive for the lifetime of the watcher
+type EventListener = Closure<dyn FnMut(Event)>;
+
+// Watches for the page losing focus: the tab being hidden or the window
+// losing focus. The browser stops animation frames in a hidden tab, so the
+// game can't pause itself from the listener; it checks `take_lost` on the
+// next frame instead.
+pub struct FocusWatcher {
+    window: Window,
+    document: Document,
+    lost: Rc<Cell<bool>>,
+    on_visibility_change: EventListener,
+    on_blur: EventListener,
+}
+
+impl FocusWatcher {
+    pub fn new() -> Result<Self, JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("no global window"))?;
+        let document = window.document().ok_or_else(|| JsValue::from_str("no global document"))?;
+        let lost = Rc::new(Cell::new(false));
+
+        let hidden_document = document.clone();
+        let visibility_lost = lost.clone();
+        let on_visibility_change = EventListener::new(move |_event: Event| {
+            if hidden_document.hidden() {
+                visibility_lost.set(true);
+            }
+        });
+        document.add_event_listener_with_callback("visibilitychange", on_visibility_change.as_ref().unchecked_ref())?;
+
+        let blur_lost = lost.clone();
+        let on_blur = EventListener::new(move |_event: Event| blur_lost.set(true));
+        window.add_event_listener_with_callback("blur", on_blur.as_ref().unchecked_ref())?;
+
+        Ok(FocusWatcher { window, document, lost, on_visibility_change, on_blur })
+    }
+
+    // Whether focus was lost since the last call
+    pub fn take_lost(&self) -> bool {
+        self.lost.replace(false)
+    }
+}
+
+impl Drop for FocusWatcher {
+    fn drop(&mut self) {
+        let _ = self.document.remove_event_listener_with_callback(
+            "visibilitychange",
+            self.on_visibility_change.as_ref().unchecked_ref(),
+        );
+        let _ = self.window.remove_event_listener_with_callback("blur", self.on_blur.as_ref().unchecked_ref());
+    }
+}
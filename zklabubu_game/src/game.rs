@@ -1,7 +1,17 @@
-use web_sys::{CanvasRenderingContext2d, KeyboardEvent, HtmlImageElement, HtmlAudioElement};
+use web_sys::{CanvasRenderingContext2d, HtmlImageElement, HtmlAudioElement};
 use crate::console_log;
 use crate::entities::{Labubu, FallingItem, FallingItemType};
-use crate::utils::random;
+use crate::hud::{Hud, HudImages, HudStats, PowerUpTimer};
+use crate::input::{Action, InputLog};
+use crate::lanes::LaneLayout;
+use crate::pointer::{PointerTracker, TapMode};
+use crate::ledger::EventLedger;
+use crate::utils::Rng;
+use zklabubu_proof_lib::{GameConfig, GameEventKind, LedgerCounts};
+
+// Longest frame the game simulates (seconds). A longer gap (a stalled tab or
+// a debugger break) is cut to this, so items can't jump past Labubu.
+const MAX_FRAME_DELTA: f64 = 0.1;
 
 // Game states - public so lib.rs can access them
 #[derive(PartialEq, Debug, Clone, Copy)]
@@ -19,12 +29,17 @@ pub struct Game {
     state: GameState,
     ctx: CanvasRenderingContext2d,
     labubu: Labubu,
+    lanes: LaneLayout,
     falling_items: Vec<FallingItem>,
     last_item_spawn_time: f64,
     spawn_interval: f64,
     score: u32,        // real score (used for proof)
     visual_score: u32, // visual score (used for UI)
     lives: u32,
+    rock_hits: u32,     // Rocks that cost a life (used for proof)
+    shield_blocks: u32, // Rocks blocked by the shield
+    extra_lives: u32,   // Extra life pickups that added a life
+    combo: u32,         // Eggs caught in a row (HUD only, not proved)
     game_over: bool,
     difficulty_multiplier: f64,
     elapsed_time: f64,
@@ -55,8 +70,24 @@ pub struct Game {
     // Sound status
     sound_enabled: bool,
     
-    // Last collected item for SP1
-    last_collected_item: Option<FallingItemType>,
+    // Spawn randomness (seeded by the session ticket when there is one)
+    rng: Rng,
+    seed: u64,
+    
+    // Events of the game (used for achievement proofs)
+    ledger: EventLedger,
+    
+    // Movement actions waiting for the next frame, and every applied action
+    pending_actions: Vec<Action>,
+    input_log: InputLog,
+    pointer: PointerTracker, // Taps and swipes on the canvas
+    
+    // Game balance (its hash is committed by the proof), and the one to play from the next restart
+    config: GameConfig,
+    pending_config: Option<GameConfig>,
+    
+    // Score, lives, level, combo and egg counters
+    hud: Hud,
 }
 
 impl Game {
@@ -86,8 +117,19 @@ impl Game {
         egg_sound: HtmlAudioElement,
         rock_sound: HtmlAudioElement,
         shield_hit_sound: HtmlAudioElement,
+        config: GameConfig,
     ) -> Self {
-        let labubu = Labubu::new(width, height, labubu_img);
+        let lanes = LaneLayout::new(config.lanes as usize, width);
+        let labubu = Labubu::new(&lanes, height, config.lane_change_ms as f64 / 1000.0, labubu_img);
+        let seed = Rng::from_entropy().next_u64();
+        let spawn_interval = config.difficulty.spawn_interval(0);
+        let lives = config.start_lives;
+        let hud = Hud::new(HudImages {
+            life: extra_life_img.clone(),
+            yellow_egg: yellow_egg_img.clone(),
+            pink_egg: pink_egg_img.clone(),
+            purple_egg: purple_egg_img.clone(),
+        });
         
         Game {
             width,
@@ -95,12 +137,17 @@ impl Game {
             state: GameState::NotStarted,
             ctx,
             labubu,
+            lanes,
             falling_items: vec![],
             last_item_spawn_time: 0.0,
-            spawn_interval: 1.2, // Initially spawn an item every 1.2 seconds
+            spawn_interval, // Initial spawn interval of the difficulty curve
             score: 0,
             visual_score: 0,
-            lives: 3,
+            lives,
+            rock_hits: 0,
+            shield_blocks: 0,
+            extra_lives: 0,
+            combo: 0,
             game_over: false,
             difficulty_multiplier: 1.0,
             elapsed_time: 0.0,
@@ -122,29 +169,106 @@ impl Game {
             rock_sound,
             shield_hit_sound,
             sound_enabled: true, // Sound is on by default
-            last_collected_item: None,
+            rng: Rng::new(seed),
+            seed,
+            ledger: EventLedger::default(),
+            pending_actions: vec![],
+            input_log: InputLog::default(),
+            pointer: PointerTracker::default(),
+            config,
+            pending_config: None,
+            hud,
         }
     }
     
+    // Use a new seed for the spawn sequence (applies from the next restart)
+    pub fn set_seed(&mut self, seed: u64) {
+        self.seed = seed;
+        self.rng = Rng::new(seed);
+    }
+    
+    // Get the seed of the spawn sequence
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+    
+    // Get the game config
+    pub fn get_config(&self) -> &GameConfig {
+        &self.config
+    }
+    
+    // Play with another config (applies from the next restart, the current game keeps its rules)
+    pub fn set_config(&mut self, config: GameConfig) {
+        self.pending_config = Some(config);
+    }
+    
+    fn apply_pending_config(&mut self) {
+        let Some(config) = self.pending_config.take() else {
+            return;
+        };
+        self.lanes = LaneLayout::new(config.lanes as usize, self.width);
+        let slide_duration = config.lane_change_ms as f64 / 1000.0;
+        self.labubu = Labubu::new(&self.lanes, self.height, slide_duration, self.labubu.img.clone());
+        self.config = config;
+    }
+    
     // Set sound status
     pub fn set_sound_enabled(&mut self, enabled: bool) {
         self.sound_enabled = enabled;
         console_log!("Game sound status: {}", if enabled { "on" } else { "off" });
     }
     
-    // Handle keyboard input
-    pub fn handle_key_press(&mut self, event: KeyboardEvent) {
-        if matches!(self.state, GameState::Playing) {
-            let key = event.key();
-            match key.as_str() {
-                "ArrowLeft" | "a" | "A" => self.labubu.move_left(),
-                "ArrowRight" | "d" | "D" => self.labubu.move_right(),
-                "p" | "P" => self.toggle_pause(),
-                _ => {}
+    // Handle a pointer or touch press on the canvas (canvas pixels)
+    pub fn handle_pointer_down(&mut self, x: f64, y: f64) {
+        self.pointer.press(x, y);
+    }
+    
+    // Handle the end of a press: a swipe or a tap
+    pub fn handle_pointer_up(&mut self, x: f64, y: f64) {
+        if let Some(action) = self.pointer.release(x, y, &self.lanes, self.width) {
+            self.handle_action(action);
+        }
+    }
+    
+    // Forget an interrupted press
+    pub fn cancel_pointer(&mut self) {
+        self.pointer.cancel();
+    }
+    
+    // Choose what a tap does
+    pub fn set_tap_mode(&mut self, tap_mode: TapMode) {
+        self.pointer.tap_mode = tap_mode;
+    }
+    
+    // Handle an action of any input device
+    pub fn handle_action(&mut self, action: Action) {
+        if !matches!(self.state, GameState::Playing) {
+            return;
+        }
+        
+        match action {
+            // Movement is applied at the start of the next frame, in game time
+            Action::MoveLeft | Action::MoveRight | Action::MoveToLane(_) => self.pending_actions.push(action),
+            Action::TogglePause => {
+                self.input_log.record(self.elapsed_time, action);
+                self.toggle_pause();
             }
         }
     }
     
+    // Apply the movement actions received since the last frame
+    fn apply_pending_actions(&mut self) {
+        for action in std::mem::take(&mut self.pending_actions) {
+            match action {
+                Action::MoveLeft => self.labubu.move_left(),
+                Action::MoveRight => self.labubu.move_right(),
+                Action::MoveToLane(lane) => self.labubu.move_to_lane(lane),
+                Action::TogglePause => continue,
+            }
+            self.input_log.record(self.elapsed_time, action);
+        }
+    }
+    
     // Start the game
     pub fn start(&mut self) {
         self.state = GameState::Playing;
@@ -167,34 +291,44 @@ impl Game {
     
     // Restart the game
     pub fn restart(&mut self) {
-        self.labubu.reset(self.width);
+        self.apply_pending_config();
+        self.labubu.reset();
         self.falling_items.clear();
         self.score = 0;
-        self.lives = 3;
+        self.lives = self.config.start_lives;
+        self.rock_hits = 0;
+        self.shield_blocks = 0;
+        self.extra_lives = 0;
+        self.combo = 0;
         self.game_over = false;
         self.difficulty_multiplier = 1.0;
         self.elapsed_time = 0.0;
         self.last_item_spawn_time = 0.0; // This line was added
-        self.spawn_interval = 1.2;
+        self.spawn_interval = self.config.difficulty.spawn_interval(0);
         self.shield_active_until = None;
         self.double_points_active_until = None;
         self.slowdown_active_until = None; // New field
         self.powerup_due = false;
-        self.last_collected_item = None;
+        self.rng = Rng::new(self.seed);
+        self.ledger.clear();
+        self.pending_actions.clear();
+        self.input_log.clear();
         self.state = GameState::Playing;
     }
     
     // Update each frame
     pub fn update(&mut self, delta_time: f64) -> bool {
-        // At the beginning of each update, reset last_collected_item
-        self.last_collected_item = None;
-        
         if !matches!(self.state, GameState::Playing) {
             return self.game_over; // Return true if game is over
         }
         
+        let delta_time = delta_time.clamp(0.0, MAX_FRAME_DELTA);
         self.elapsed_time += delta_time;
         
+        // Move Labubu (collisions below use the interpolated position)
+        self.apply_pending_actions();
+        self.labubu.update(delta_time);
+        
         // At game start or after restart, immediately start dropping items
         if self.elapsed_time < 0.5 && self.last_item_spawn_time == 0.0 && self.falling_items.is_empty() {
             // Drop an item immediately
@@ -230,38 +364,30 @@ impl Game {
             }
         }
         
-        // Update difficulty level (every 100 points)
-        let difficulty_level = if self.score < 500 {
-            // Difficulty increases every 100 points up to 500
-            (self.score / 100) as f64
-        } else {
-            // After 500 points, difficulty increases every 500 points
-            5.0 + ((self.score - 500) / 500) as f64
-        };
-
-        // Calculate difficulty multiplier (40% harder each level)
-        let base_multiplier = 1.0 + (difficulty_level * 0.40);
+        // Update difficulty level (from the difficulty curve of the config)
+        let difficulty = &self.config.difficulty;
+        let difficulty_level = difficulty.level(self.score);
 
-        // If slowdown is active, reduce difficulty
-        if self.slowdown_active_until.is_some() {
-            self.difficulty_multiplier = base_multiplier * 0.6; // 40% easier
-        } else {
-            self.difficulty_multiplier = base_multiplier;
-        }
+        // Items fall faster each level, slower while a slowdown is active
+        self.difficulty_multiplier = difficulty.speed_multiplier(difficulty_level, self.slowdown_active_until.is_some());
 
         // Update item spawn interval (based on difficulty)
-        self.spawn_interval = (1.2 - (difficulty_level * 0.1)).max(0.4); // Minimum 0.4 seconds (increased from 0.3)
+        self.spawn_interval = difficulty.spawn_interval(difficulty_level);
         
-        // Check for power-up drop every 100 points
-        let current_powerup_level = self.score / 100;
-        let previous_powerup_level = if self.score > 0 { (self.score - 1) / 100 } else { 0 };
+        // Check for power-up drop every `power_up_score_step` points
+        let step = self.config.power_up_score_step;
+        let current_powerup_level = self.score / step;
+        let previous_powerup_level = if self.score > 0 { (self.score - 1) / step } else { 0 };
         
         if current_powerup_level > previous_powerup_level {
-            console_log!("Power-up time! A power-up drops every 100 points.");
+            console_log!("Power-up time! A power-up drops every {} points.", step);
             // Drop a power-up on the next spawn
             self.powerup_due = true;
         }
         
+        // Power-ups last the same time
+        let power_up_duration = self.config.power_up_duration_ms as f64 / 1000.0;
+        
         // Update falling items
         let mut items_to_remove = vec![];
         
@@ -271,6 +397,11 @@ impl Game {
             // Is it off screen?
             if item.y > self.height {
                 items_to_remove.push(i);
+                
+                // A missed egg ends the combo
+                if item.is_egg() {
+                    self.combo = 0;
+                }
             }
             
             // Does it collide with the crab?
@@ -282,6 +413,7 @@ impl Game {
                         
                         // ADD BASE POINTS TO SCORE (without 2x effect)
                         self.score += base_points;
+                        self.combo += 1;
                         
                         // If 2x points is active, show extra points in visual score
                         if self.double_points_active_until.is_some() {
@@ -294,14 +426,21 @@ impl Game {
                             let _ = self.egg_sound.play().unwrap();
                         }
                         
-                        // Save the last collected item
-                        self.last_collected_item = Some(item.item_type);
+                        let egg = match item.item_type {
+                            FallingItemType::YellowEgg => GameEventKind::YellowEgg,
+                            FallingItemType::PinkEgg => GameEventKind::PinkEgg,
+                            _ => GameEventKind::PurpleEgg,
+                        };
+                        self.ledger.record(self.elapsed_time, egg);
                     },
                     FallingItemType::Rock => {
                         // Hit by a rock, lose a life (if shield not active)
                         if self.shield_active_until.is_none() {
                             if self.lives > 0 {
                                 self.lives -= 1;
+                                self.rock_hits += 1;
+                                self.combo = 0;
+                                self.ledger.record(self.elapsed_time, GameEventKind::RockHit);
                                 if self.sound_enabled {
                                     let _ = self.rock_sound.play().unwrap();
                                 }
@@ -315,6 +454,8 @@ impl Game {
                             }
                         } else {
                             console_log!("Shield prevented collision!");
+                            self.shield_blocks += 1;
+                            self.ledger.record(self.elapsed_time, GameEventKind::ShieldBlock);
                             // Play special sound if shield is active
                             if self.sound_enabled {
                                 let _ = self.shield_hit_sound.play().unwrap();
@@ -323,24 +464,26 @@ impl Game {
                     },
                     FallingItemType::Shield => {
                         // Shield power-up collected
-                        self.shield_active_until = Some(self.elapsed_time + 10.0); // 10 seconds of protection
-                        console_log!("Shield activated! 10 seconds of protection.");
+                        self.shield_active_until = Some(self.elapsed_time + power_up_duration);
+                        console_log!("Shield activated! {} seconds of protection.", power_up_duration);
                         if self.sound_enabled {
                             let _ = self.egg_sound.play().unwrap();
                         }
                     },
                     FallingItemType::DoublePoints => {
                         // 2x points power-up collected
-                        self.double_points_active_until = Some(self.elapsed_time + 10.0); // 10 seconds of 2x points
-                        console_log!("2x points activated! Will last 10 seconds.");
+                        self.double_points_active_until = Some(self.elapsed_time + power_up_duration);
+                        console_log!("2x points activated! Will last {} seconds.", power_up_duration);
                         if self.sound_enabled {
                             let _ = self.egg_sound.play().unwrap();
                         }
                     },
                     FallingItemType::ExtraLife => {
                         // Extra life power-up collected
-                        if self.lives < 5 { // Maximum 5 lives
+                        if self.lives < self.config.max_lives { // Lives are capped
                             self.lives += 1;
+                            self.extra_lives += 1;
+                            self.ledger.record(self.elapsed_time, GameEventKind::ExtraLife);
                             console_log!("Extra life gained! Total lives: {}", self.lives);
                             if self.sound_enabled {
                                 let _ = self.egg_sound.play().unwrap();
@@ -354,8 +497,8 @@ impl Game {
                     },
                     FallingItemType::SlowDown => {
                         // Slowdown power-up collected
-                        self.slowdown_active_until = Some(self.elapsed_time + 10.0); // 10 seconds of slowdown
-                        console_log!("Slowdown activated! Will last 10 seconds.");
+                        self.slowdown_active_until = Some(self.elapsed_time + power_up_duration);
+                        console_log!("Slowdown activated! Will last {} seconds.", power_up_duration);
                         if self.sound_enabled {
                             let _ = self.egg_sound.play().unwrap();
                         }
@@ -380,19 +523,18 @@ impl Game {
     // Spawn falling items
     fn spawn_falling_items(&mut self) {
         // Determine number of items to drop based on difficulty
-        let difficulty_level = (self.score / 100) as usize;
-        let max_items = (1 + difficulty_level).min(3); // Maximum 3 items
+        let max_items = self.config.difficulty.items_per_spawn(self.score) as usize;
         
         // Randomly create 1-max_items items
-        let num_items = 1 + (random(0.0, max_items as f64) as usize);
+        let num_items = 1 + (self.rng.range(0.0, max_items as f64) as usize);
         
         // Track which lanes are used
-        let mut used_lanes = vec![false; 5];
+        let mut used_lanes = vec![false; self.lanes.count()];
         
         // If it's time to drop a power-up
         if self.powerup_due {
             // Create a random power-up
-            let lane = (random(0.0, 5.0)) as usize;
+            let lane = self.lanes.random_lane(&mut self.rng);
             used_lanes[lane] = true;
             
             // Determine power-up type
@@ -402,13 +544,13 @@ impl Game {
                 FallingItemType::ExtraLife
             ];
             
-            // Add slowdown power-up after 1000 points
-            if self.score >= 400 {
+            // Add slowdown power-up from `slowdown_min_score` points on
+            if self.score >= self.config.slowdown_min_score {
                 possible_powerups.push(FallingItemType::SlowDown);
             }
             
             // Choose a random power-up
-            let rand_index = (random(0.0, possible_powerups.len() as f64)) as usize;
+            let rand_index = (self.rng.range(0.0, possible_powerups.len() as f64)) as usize;
             let powerup_type = possible_powerups[rand_index];
             
             // Select appropriate image for the power-up
@@ -420,12 +562,13 @@ impl Game {
                 _ => self.yellow_egg_img.clone(),
             };
             
+            let size = self.lanes.fit(100.0);
             let item = FallingItem {
-                x: (lane as f64 * (self.width / 5.0)) + ((self.width / 5.0) - 100.0) / 2.0,
-                y: -100.0,
-                width: 100.0,
-                height: 100.0,
-                speed: 200.0 * self.difficulty_multiplier,
+                x: self.lanes.x(lane, size),
+                y: -size,
+                width: size,
+                height: size,
+                speed: self.config.base_speed as f64 * self.difficulty_multiplier,
                 item_type: powerup_type,
                 lane,
                 img,
@@ -439,12 +582,9 @@ impl Game {
         
         for _ in 0..num_items {
             // Choose an unused random lane
-            let mut available_lanes = Vec::new();
-            for lane in 0..5 {
-                if !used_lanes[lane] {
-                    available_lanes.push(lane);
-                }
-            }
+            let available_lanes: Vec<usize> = (0..self.lanes.count())
+                .filter(|&lane| !used_lanes[lane])
+                .collect();
             
             // Exit if all lanes are used
             if available_lanes.is_empty() {
@@ -452,13 +592,15 @@ impl Game {
             }
             
             // Choose a random unused lane
-            let lane_index = (random(0.0, available_lanes.len() as f64)) as usize;
+            let lane_index = (self.rng.range(0.0, available_lanes.len() as f64)) as usize;
             let selected_lane = available_lanes[lane_index];
             used_lanes[selected_lane] = true;
             
             // Create a new falling item in the selected lane
             let mut item = FallingItem::new(
-                self.width,
+                &mut self.rng,
+                &self.config,
+                &self.lanes,
                 self.difficulty_multiplier,
                 self.yellow_egg_img.clone(),
                 self.pink_egg_img.clone(),
@@ -467,9 +609,8 @@ impl Game {
             );
             
             // Place the item in the selected lane
-            let lane_width = self.width / 5.0;
             item.lane = selected_lane;
-            item.x = (selected_lane as f64 * lane_width) + (lane_width - item.width) / 2.0;
+            item.x = self.lanes.x(selected_lane, item.width);
             
             self.falling_items.push(item);
         }
@@ -503,18 +644,8 @@ impl Game {
             self.labubu.height,
         ).unwrap();
         
-        // Show active power-ups
-        let mut y_offset = 50.0;
-        
-        if let Some(shield_end_time) = self.shield_active_until {
-            let remaining = (shield_end_time - self.elapsed_time).max(0.0);
-            self.ctx.set_fill_style(&"rgba(100, 100, 255, 0.7)".into());
-            self.ctx.fill_rect(10.0, y_offset, 30.0, 30.0);
-            self.ctx.set_font("16px Arial");
-            self.ctx.set_fill_style(&"white".into());
-            self.ctx.fill_text(&format!("Shield: {:.1}s", remaining), 50.0, y_offset + 20.0).unwrap();
-            
-            // Draw a ring around the labubu if shield is active
+        // Draw a ring around the labubu if shield is active
+        if self.shield_active_until.is_some() {
             self.ctx.set_stroke_style(&"rgba(100, 100, 255, 0.7)".into());
             self.ctx.set_line_width(3.0);
             self.ctx.begin_path();
@@ -526,29 +657,49 @@ impl Game {
                 std::f64::consts::PI * 2.0,
             ).unwrap();
             self.ctx.stroke();
-            
-            y_offset += 40.0;
         }
         
-        if let Some(double_points_end_time) = self.double_points_active_until {
-            let remaining = (double_points_end_time - self.elapsed_time).max(0.0);
-            self.ctx.set_fill_style(&"rgba(255, 100, 100, 0.7)".into());
-            self.ctx.fill_rect(10.0, y_offset, 30.0, 30.0);
-            self.ctx.set_font("16px Arial");
-            self.ctx.set_fill_style(&"white".into());
-            self.ctx.fill_text(&format!("2x Points: {:.1}s", remaining), 50.0, y_offset + 20.0).unwrap();
-            
-            y_offset += 40.0;
-        }
-        
-        if let Some(slowdown_end_time) = self.slowdown_active_until {
-            let remaining = (slowdown_end_time - self.elapsed_time).max(0.0);
-            self.ctx.set_fill_style(&"rgba(100, 255, 100, 0.7)".into());
-            self.ctx.fill_rect(10.0, y_offset, 30.0, 30.0);
-            self.ctx.set_font("16px Arial");
-            self.ctx.set_fill_style(&"white".into());
-            self.ctx.fill_text(&format!("Slowdown: {:.1}s", remaining), 50.0, y_offset + 20.0).unwrap();
-        }
+        // Show active power-ups
+        let power_ups = [
+            (self.shield_active_until, "Shield", "rgba(100, 100, 255, 0.7)"),
+            (self.double_points_active_until, "2x Points", "rgba(255, 100, 100, 0.7)"),
+            (self.slowdown_active_until, "Slowdown", "rgba(100, 255, 100, 0.7)"),
+        ];
+        let stats = HudStats {
+            score: self.visual_score,
+            lives: self.lives,
+            max_lives: self.config.max_lives,
+            level: self.get_level(),
+            combo: self.combo,
+            eggs: self.ledger.counts(),
+            power_ups: power_ups
+                .into_iter()
+                .filter_map(|(active_until, label, color)| {
+                    active_until.map(|end_time| PowerUpTimer { label, color, remaining: end_time - self.elapsed_time })
+                })
+                .collect(),
+        };
+        self.hud.draw(&self.ctx, self.width, self.height, &stats);
+    }
+    
+    // Change the HUD mode or layout
+    pub fn hud_mut(&mut self) -> &mut Hud {
+        &mut self.hud
+    }
+    
+    // Get the HUD (mode and layout)
+    pub fn hud(&self) -> &Hud {
+        &self.hud
+    }
+    
+    // Get the difficulty level (from 1)
+    pub fn get_level(&self) -> u32 {
+        self.config.difficulty.level(self.score) + 1
+    }
+    
+    // Get the eggs caught in a row
+    pub fn get_combo(&self) -> u32 {
+        self.combo
     }
     
     // Get the score
@@ -557,7 +708,6 @@ impl Game {
     }
     
     // Get the visual score (for UI)
-    #[allow(dead_code)]
     pub fn get_visual_score(&self) -> u32 {
         self.visual_score
     }
@@ -567,6 +717,21 @@ impl Game {
         self.lives
     }
     
+    // Get the rocks that cost a life
+    pub fn get_rock_hits(&self) -> u32 {
+        self.rock_hits
+    }
+    
+    // Get the rocks blocked by the shield
+    pub fn get_shield_blocks(&self) -> u32 {
+        self.shield_blocks
+    }
+    
+    // Get the extra lives gained
+    pub fn get_extra_lives(&self) -> u32 {
+        self.extra_lives
+    }
+    
     // Is the game over?
     pub fn is_game_over(&self) -> bool {
         self.game_over
@@ -577,9 +742,24 @@ impl Game {
         self.state
     }
     
-    // Get the last collected item
-    pub fn get_last_collected_item(&self) -> Option<FallingItemType> {
-        self.last_collected_item
+    // Counts replayed from the event ledger
+    pub fn get_ledger_counts(&self) -> &LedgerCounts {
+        self.ledger.counts()
+    }
+    
+    // Get the event ledger (JSON)
+    pub fn get_event_ledger(&self) -> String {
+        self.ledger.to_json()
+    }
+    
+    // Get the input log (JSON)
+    pub fn get_input_log(&self) -> String {
+        self.input_log.to_json()
+    }
+    
+    // Get the achievement progress (JSON)
+    pub fn get_achievement_progress(&self) -> String {
+        self.ledger.progress_json()
     }
     
     // Get the elapsed time
@@ -0,0 +1,148 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Performance, Window};
+
+// Browser frame callback (gets the `requestAnimationFrame` timestamp)
+type FrameListener = Closure<dyn FnMut(f64)>;
+
+// The frame rate is averaged over this long (milliseconds)
+const FPS_WINDOW_MS: f64 = 1000.0;
+
+// Measures frame times with `performance.now()` and averages the frame rate
+#[derive(Default)]
+struct FrameClock {
+    last_ms: Option<f64>,
+    window_start_ms: f64,
+    window_frames: u32,
+    fps: f64,
+}
+
+impl FrameClock {
+    // Time since the previous frame (seconds, 0 for the first one)
+    fn tick(&mut self, now_ms: f64) -> f64 {
+        let Some(last_ms) = self.last_ms.replace(now_ms) else {
+            self.window_start_ms = now_ms;
+            return 0.0;
+        };
+
+        self.window_frames += 1;
+        let window_ms = now_ms - self.window_start_ms;
+        if window_ms >= FPS_WINDOW_MS {
+            self.fps = self.window_frames as f64 * 1000.0 / window_ms;
+            self.window_start_ms = now_ms;
+            self.window_frames = 0;
+        }
+        (now_ms - last_ms).max(0.0) / 1000.0
+    }
+}
+
+// State shared with the `requestAnimationFrame` callback
+struct LoopState {
+    window: Window,
+    performance: Performance,
+    running: Cell<bool>,
+    frame_id: Cell<Option<i32>>,
+    clock: RefCell<FrameClock>,
+    on_frame: RefCell<Box<dyn FnMut(f64)>>,
+    callback: RefCell<Option<FrameListener>>,
+}
+
+impl LoopState {
+    fn frame(&self) {
+        self.frame_id.set(None);
+        if !self.running.get() {
+            return;
+        }
+
+        let delta_time = self.clock.borrow_mut().tick(self.performance.now());
+        (self.on_frame.borrow_mut())(delta_time);
+
+        // The frame hook may have stopped the loop, or stopped and restarted it
+        if self.running.get() && self.frame_id.get().is_none() {
+            self.request_frame();
+        }
+    }
+
+    fn request_frame(&self) {
+        if let Some(callback) = self.callback.borrow().as_ref() {
+            match self.window.request_animation_frame(callback.as_ref().unchecked_ref()) {
+                Ok(id) => self.frame_id.set(Some(id)),
+                Err(_) => self.running.set(false),
+            }
+        }
+    }
+}
+
+// Game loop driven by `requestAnimationFrame`: calls `on_frame` with the time
+// since the previous frame (seconds) until stopped
+pub struct AnimationLoop {
+    state: Rc<LoopState>,
+}
+
+impl AnimationLoop {
+    pub fn new(on_frame: impl FnMut(f64) + 'static) -> Result<Self, JsValue> {
+        let window = web_sys::window().ok_or_else(|| JsValue::from_str("no global window"))?;
+        let performance = window.performance().ok_or_else(|| JsValue::from_str("no performance timer"))?;
+        let state = Rc::new(LoopState {
+            window,
+            performance,
+            running: Cell::new(false),
+            frame_id: Cell::new(None),
+            clock: RefCell::new(FrameClock::default()),
+            on_frame: RefCell::new(Box::new(on_frame)),
+            callback: RefCell::new(None),
+        });
+
+        // A weak reference, so the callback doesn't keep its own state alive
+        let weak_state = Rc::downgrade(&state);
+        let callback = FrameListener::new(move |_timestamp: f64| {
+            if let Some(state) = weak_state.upgrade() {
+                state.frame();
+            }
+        });
+        *state.callback.borrow_mut() = Some(callback);
+
+        Ok(AnimationLoop { state })
+    }
+
+    // Start requesting frames (the first frame has a zero delta)
+    pub fn start(&self) {
+        if self.state.running.replace(true) {
+            return;
+        }
+        *self.state.clock.borrow_mut() = FrameClock::default();
+        if self.state.frame_id.get().is_none() {
+            self.state.request_frame();
+        }
+    }
+
+    // Stop requesting frames
+    pub fn stop(&self) {
+        self.state.running.set(false);
+        if let Some(id) = self.state.frame_id.take() {
+            let _ = self.state.window.cancel_animation_frame(id);
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state.running.get()
+    }
+
+    // Frames per second over the last second (0 when stopped)
+    pub fn fps(&self) -> f64 {
+        if self.is_running() {
+            self.state.clock.borrow().fps
+        } else {
+            0.0
+        }
+    }
+}
+
+impl Drop for AnimationLoop {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
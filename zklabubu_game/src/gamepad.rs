@@ -0,0 +1,90 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Gamepad, GamepadButton};
+
+use crate::input::Action;
+
+// Buttons and axes of the standard gamepad mapping
+const BUTTON_START: u32 = 9;
+const BUTTON_DPAD_LEFT: u32 = 14;
+const BUTTON_DPAD_RIGHT: u32 = 15;
+const AXIS_LEFT_STICK_X: u32 = 0;
+
+// Default stick deadzone (fraction of the full tilt)
+pub const DEFAULT_DEADZONE: f64 = 0.5;
+
+// Inputs held at the last poll, so a held direction moves only once
+#[derive(Default, Clone, Copy, PartialEq)]
+struct Held {
+    left: bool,
+    right: bool,
+    pause: bool,
+}
+
+// Polls the browser Gamepad API and turns presses into actions: D-pad or
+// left stick move one lane per push, start toggles the pause
+pub struct GamepadInput {
+    deadzone: f64,
+    held: Held,
+}
+
+impl Default for GamepadInput {
+    fn default() -> Self {
+        GamepadInput { deadzone: DEFAULT_DEADZONE, held: Held::default() }
+    }
+}
+
+impl GamepadInput {
+    // Stick tilt below the deadzone is ignored
+    pub fn set_deadzone(&mut self, deadzone: f64) {
+        self.deadzone = deadzone.clamp(0.05, 0.95);
+    }
+
+    pub fn deadzone(&self) -> f64 {
+        self.deadzone
+    }
+
+    // Actions pressed since the last poll, from every connected gamepad
+    pub fn poll(&mut self) -> Vec<Action> {
+        let held = self.read_held();
+        let mut actions = vec![];
+        if held.left && !self.held.left {
+            actions.push(Action::MoveLeft);
+        }
+        if held.right && !self.held.right {
+            actions.push(Action::MoveRight);
+        }
+        if held.pause && !self.held.pause {
+            actions.push(Action::TogglePause);
+        }
+        self.held = held;
+        actions
+    }
+
+    fn read_held(&self) -> Held {
+        let mut held = Held::default();
+        let Some(gamepads) = web_sys::window().and_then(|window| window.navigator().get_gamepads().ok()) else {
+            return held;
+        };
+
+        // Disconnected slots are null
+        for gamepad in gamepads.iter().filter_map(|gamepad| gamepad.dyn_into::<Gamepad>().ok()) {
+            if !gamepad.connected() {
+                continue;
+            }
+            let stick_x = gamepad.axes().get(AXIS_LEFT_STICK_X).as_f64().unwrap_or(0.0);
+            held.left |= button_pressed(&gamepad, BUTTON_DPAD_LEFT) || stick_x < -self.deadzone;
+            held.right |= button_pressed(&gamepad, BUTTON_DPAD_RIGHT) || stick_x > self.deadzone;
+            held.pause |= button_pressed(&gamepad, BUTTON_START);
+        }
+        held
+    }
+}
+
+fn button_pressed(gamepad: &Gamepad, index: u32) -> bool {
+    gamepad
+        .buttons()
+        .get(index)
+        .dyn_into::<GamepadButton>()
+        .map(|button| button.pressed())
+        .unwrap_or(false)
+}
@@ -0,0 +1,381 @@
+use std::fmt;
+
+use web_sys::{CanvasRenderingContext2d, HtmlImageElement};
+use zklabubu_proof_lib::LedgerCounts;
+
+// With more lives than this, the HUD shows one icon and a count
+const MAX_LIFE_ICONS: u32 = 10;
+
+// Shortest streak the combo indicator shows
+const MIN_COMBO_SHOWN: u32 = 2;
+
+// Font size and margin limits (canvas pixels)
+const FONT_SIZE_RANGE: (f64, f64) = (8.0, 64.0);
+const MARGIN_RANGE: (f64, f64) = (0.0, 200.0);
+
+// Who draws the score, lives and egg counters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HudMode {
+    #[default]
+    Canvas,  // The game draws the whole HUD on the canvas
+    Overlay, // The page draws them in HTML (from the getters); the canvas keeps the power-up timers
+}
+
+impl HudMode {
+    pub fn from_name(name: &str) -> Option<HudMode> {
+        match name {
+            "canvas" => Some(HudMode::Canvas),
+            "overlay" => Some(HudMode::Overlay),
+            _ => None,
+        }
+    }
+}
+
+// Parts of the HUD
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HudElement {
+    Score,
+    Level,
+    Combo,
+    PowerUps,
+    Lives,
+    Eggs,
+}
+
+impl HudElement {
+    // Drawing order: elements in the same corner stack in this order
+    const ALL: [HudElement; 6] = [
+        HudElement::Score,
+        HudElement::Level,
+        HudElement::Combo,
+        HudElement::PowerUps,
+        HudElement::Lives,
+        HudElement::Eggs,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            HudElement::Score => "score",
+            HudElement::Level => "level",
+            HudElement::Combo => "combo",
+            HudElement::PowerUps => "powerUps",
+            HudElement::Lives => "lives",
+            HudElement::Eggs => "eggs",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<HudElement> {
+        HudElement::ALL.into_iter().find(|element| element.name() == name)
+    }
+}
+
+// Canvas corner an element is drawn in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Hidden,
+}
+
+impl Anchor {
+    const ALL: [Anchor; 5] = [Anchor::TopLeft, Anchor::TopRight, Anchor::BottomLeft, Anchor::BottomRight, Anchor::Hidden];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Anchor::TopLeft => "top_left",
+            Anchor::TopRight => "top_right",
+            Anchor::BottomLeft => "bottom_left",
+            Anchor::BottomRight => "bottom_right",
+            Anchor::Hidden => "hidden",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Anchor> {
+        Anchor::ALL.into_iter().find(|anchor| anchor.name() == name)
+    }
+
+    fn is_right(self) -> bool {
+        matches!(self, Anchor::TopRight | Anchor::BottomRight)
+    }
+
+    fn is_bottom(self) -> bool {
+        matches!(self, Anchor::BottomLeft | Anchor::BottomRight)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HudLayoutError {
+    Json(String),
+    UnknownElement(String),
+    UnknownAnchor { element: String, anchor: String },
+    OutOfRange { field: &'static str, min: f64, max: f64 },
+}
+
+impl fmt::Display for HudLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HudLayoutError::Json(err) => write!(f, "HUD layout is not valid JSON: {}", err),
+            HudLayoutError::UnknownElement(name) => write!(f, "Unknown HUD element: {}", name),
+            HudLayoutError::UnknownAnchor { element, anchor } => {
+                write!(f, "Unknown position for {}: {} (use top_left, top_right, bottom_left, bottom_right or hidden)", element, anchor)
+            }
+            HudLayoutError::OutOfRange { field, min, max } => write!(f, "{} must be a number from {} to {}", field, min, max),
+        }
+    }
+}
+
+// Where each HUD element goes, and how big the HUD is
+#[derive(Clone, Debug, PartialEq)]
+pub struct HudLayout {
+    anchors: Vec<(HudElement, Anchor)>,
+    font_size: f64, // Text size; icons and rows scale with it (canvas pixels)
+    margin: f64,    // Distance from the canvas edges (canvas pixels)
+}
+
+impl Default for HudLayout {
+    fn default() -> Self {
+        HudLayout {
+            anchors: vec![
+                (HudElement::Score, Anchor::TopLeft),
+                (HudElement::Level, Anchor::TopLeft),
+                (HudElement::Combo, Anchor::TopLeft),
+                (HudElement::PowerUps, Anchor::TopLeft),
+                (HudElement::Lives, Anchor::TopRight),
+                (HudElement::Eggs, Anchor::TopRight),
+            ],
+            font_size: 20.0,
+            margin: 12.0,
+        }
+    }
+}
+
+impl HudLayout {
+    pub fn anchor(&self, element: HudElement) -> Anchor {
+        self.anchors
+            .iter()
+            .find(|(placed, _)| *placed == element)
+            .map_or(Anchor::Hidden, |(_, anchor)| *anchor)
+    }
+
+    // Change the layout from JSON, e.g. {"score": "top_right", "combo": "hidden", "fontSize": 24, "margin": 8}.
+    // Keys that are left out keep their current value.
+    pub fn update_from_json(&mut self, json: &str) -> Result<(), HudLayoutError> {
+        let changes: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(json).map_err(|err| HudLayoutError::Json(err.to_string()))?;
+
+        // Check everything first, so a bad layout changes nothing
+        let mut layout = self.clone();
+        for (key, value) in &changes {
+            match key.as_str() {
+                "fontSize" => layout.font_size = number_in_range(value, "fontSize", FONT_SIZE_RANGE)?,
+                "margin" => layout.margin = number_in_range(value, "margin", MARGIN_RANGE)?,
+                name => {
+                    let element = HudElement::from_name(name).ok_or_else(|| HudLayoutError::UnknownElement(name.to_string()))?;
+                    let anchor = value.as_str().and_then(Anchor::from_name).ok_or_else(|| HudLayoutError::UnknownAnchor {
+                        element: name.to_string(),
+                        anchor: value.to_string(),
+                    })?;
+                    if let Some((_, placed)) = layout.anchors.iter_mut().find(|(placed, _)| *placed == element) {
+                        *placed = anchor;
+                    }
+                }
+            }
+        }
+        *self = layout;
+        Ok(())
+    }
+
+    // Layout as JSON (the format `update_from_json` reads)
+    pub fn to_json(&self) -> String {
+        let mut map: serde_json::Map<_, _> = self
+            .anchors
+            .iter()
+            .map(|(element, anchor)| (element.name().to_string(), serde_json::json!(anchor.name())))
+            .collect();
+        map.insert("fontSize".to_string(), serde_json::json!(self.font_size));
+        map.insert("margin".to_string(), serde_json::json!(self.margin));
+        serde_json::Value::Object(map).to_string()
+    }
+}
+
+fn number_in_range(value: &serde_json::Value, field: &'static str, (min, max): (f64, f64)) -> Result<f64, HudLayoutError> {
+    value
+        .as_f64()
+        .filter(|number| (min..=max).contains(number))
+        .ok_or(HudLayoutError::OutOfRange { field, min, max })
+}
+
+// A running power-up and the seconds it has left
+pub struct PowerUpTimer {
+    pub label: &'static str,
+    pub color: &'static str,
+    pub remaining: f64,
+}
+
+// What the HUD shows this frame
+pub struct HudStats<'a> {
+    pub score: u32, // Visual score (includes the double-points bonus)
+    pub lives: u32,
+    pub max_lives: u32,
+    pub level: u32, // Difficulty level, from 1
+    pub combo: u32,
+    pub eggs: &'a LedgerCounts,
+    pub power_ups: Vec<PowerUpTimer>,
+}
+
+// Icons drawn by the HUD
+pub struct HudImages {
+    pub life: HtmlImageElement,
+    pub yellow_egg: HtmlImageElement,
+    pub pink_egg: HtmlImageElement,
+    pub purple_egg: HtmlImageElement,
+}
+
+// Draws the heads-up display over the game
+pub struct Hud {
+    pub mode: HudMode,
+    pub layout: HudLayout,
+    images: HudImages,
+}
+
+impl Hud {
+    pub fn new(images: HudImages) -> Self {
+        Hud { mode: HudMode::default(), layout: HudLayout::default(), images }
+    }
+
+    pub fn draw(&self, ctx: &CanvasRenderingContext2d, width: f64, height: f64, stats: &HudStats) {
+        ctx.save();
+        ctx.set_text_baseline("top");
+        ctx.set_font(&format!("bold {}px Arial", self.layout.font_size));
+        ctx.set_fill_style_str("white");
+        ctx.set_shadow_color("rgba(0, 0, 0, 0.6)");
+        ctx.set_shadow_blur(4.0);
+
+        // Height used so far in each corner: top left, top right, bottom left, bottom right
+        let mut used = [0.0; 4];
+        for element in HudElement::ALL {
+            // The page draws everything but the power-up timers in overlay mode
+            if self.mode == HudMode::Overlay && element != HudElement::PowerUps {
+                continue;
+            }
+            let anchor = self.layout.anchor(element);
+            let corner = match anchor {
+                Anchor::TopLeft => 0,
+                Anchor::TopRight => 1,
+                Anchor::BottomLeft => 2,
+                Anchor::BottomRight => 3,
+                Anchor::Hidden => continue,
+            };
+
+            let row_height = self.element_height(element, stats);
+            if row_height <= 0.0 {
+                continue;
+            }
+            let y = if anchor.is_bottom() {
+                height - self.layout.margin - used[corner] - row_height
+            } else {
+                self.layout.margin + used[corner]
+            };
+            let x = if anchor.is_right() { width - self.layout.margin } else { self.layout.margin };
+            self.draw_element(ctx, element, x, y, anchor.is_right(), stats);
+            used[corner] += row_height;
+        }
+
+        ctx.restore();
+    }
+
+    // Height of one line of the HUD
+    fn line_height(&self) -> f64 {
+        self.layout.font_size * 1.5
+    }
+
+    fn element_height(&self, element: HudElement, stats: &HudStats) -> f64 {
+        match element {
+            HudElement::Combo if stats.combo < MIN_COMBO_SHOWN => 0.0,
+            HudElement::PowerUps => stats.power_ups.len() as f64 * self.line_height(),
+            _ => self.line_height(),
+        }
+    }
+
+    fn draw_element(&self, ctx: &CanvasRenderingContext2d, element: HudElement, x: f64, y: f64, right: bool, stats: &HudStats) {
+        ctx.set_text_align(if right { "right" } else { "left" });
+        match element {
+            HudElement::Score => {
+                let _ = ctx.fill_text(&format!("Score: {}", stats.score), x, y);
+            }
+            HudElement::Level => {
+                let _ = ctx.fill_text(&format!("Level {}", stats.level), x, y);
+            }
+            HudElement::Combo => {
+                ctx.set_fill_style_str("#fe22be");
+                let _ = ctx.fill_text(&format!("Combo x{}", stats.combo), x, y);
+                ctx.set_fill_style_str("white");
+            }
+            HudElement::PowerUps => self.draw_power_ups(ctx, x, y, right, &stats.power_ups),
+            HudElement::Lives => self.draw_lives(ctx, x, y, right, stats.lives, stats.max_lives),
+            HudElement::Eggs => {
+                let tallies = [
+                    (&self.images.yellow_egg, stats.eggs.yellow_eggs),
+                    (&self.images.pink_egg, stats.eggs.pink_eggs),
+                    (&self.images.purple_egg, stats.eggs.purple_eggs),
+                ];
+                self.draw_counters(ctx, x, y, right, &tallies);
+            }
+        }
+    }
+
+    // One life icon per life, faded for the lives that can still be gained
+    fn draw_lives(&self, ctx: &CanvasRenderingContext2d, x: f64, y: f64, right: bool, lives: u32, max_lives: u32) {
+        if max_lives > MAX_LIFE_ICONS {
+            self.draw_counters(ctx, x, y, right, &[(&self.images.life, lives)]);
+            return;
+        }
+
+        let icon = self.layout.font_size * 1.2;
+        let step = icon + self.layout.font_size * 0.25;
+        let start_x = if right { x - max_lives as f64 * step } else { x };
+        for slot in 0..max_lives {
+            ctx.set_global_alpha(if slot < lives { 1.0 } else { 0.25 });
+            self.draw_icon(ctx, &self.images.life, start_x + slot as f64 * step, y, icon);
+        }
+        ctx.set_global_alpha(1.0);
+    }
+
+    // Icons with a count next to each (fixed-width slots, so the row doesn't jitter)
+    fn draw_counters(&self, ctx: &CanvasRenderingContext2d, x: f64, y: f64, right: bool, counters: &[(&HtmlImageElement, u32)]) {
+        let icon = self.layout.font_size * 1.2;
+        let slot = icon + self.layout.font_size * 2.5;
+        let start_x = if right { x - counters.len() as f64 * slot } else { x };
+
+        ctx.set_text_align("left");
+        for (index, (image, count)) in counters.iter().enumerate() {
+            let slot_x = start_x + index as f64 * slot;
+            self.draw_icon(ctx, image, slot_x, y, icon);
+            let _ = ctx.fill_text(&count.to_string(), slot_x + icon + self.layout.font_size * 0.25, y);
+        }
+    }
+
+    // Coloured square and seconds left of each running power-up
+    fn draw_power_ups(&self, ctx: &CanvasRenderingContext2d, x: f64, y: f64, right: bool, power_ups: &[PowerUpTimer]) {
+        let size = self.layout.font_size;
+        let gap = size * 0.5;
+        for (index, power_up) in power_ups.iter().enumerate() {
+            let row_y = y + index as f64 * self.line_height();
+            let (square_x, text_x) = if right { (x - size, x - size - gap) } else { (x, x + size + gap) };
+
+            ctx.set_fill_style_str(power_up.color);
+            ctx.fill_rect(square_x, row_y, size, size);
+            ctx.set_fill_style_str("white");
+            let _ = ctx.fill_text(&format!("{}: {:.1}s", power_up.label, power_up.remaining.max(0.0)), text_x, row_y);
+        }
+    }
+
+    fn draw_icon(&self, ctx: &CanvasRenderingContext2d, image: &HtmlImageElement, x: f64, y: f64, size: f64) {
+        // Images that haven't loaded yet are skipped
+        if image.complete() && image.natural_width() > 0 {
+            let _ = ctx.draw_image_with_html_image_element_and_dw_and_dh(image, x, y, size, size);
+        }
+    }
+}
@@ -0,0 +1,60 @@
+// Abstract player actions. Every input device (keyboard, pointer and touch)
+// is mapped onto these, so the game only ever sees actions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    MoveLeft,
+    MoveRight,
+    MoveToLane(usize),
+    TogglePause,
+}
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveLeft => "move_left",
+            Action::MoveRight => "move_right",
+            Action::MoveToLane(_) => "move_to_lane",
+            Action::TogglePause => "toggle_pause",
+        }
+    }
+}
+
+// One applied action and the game time it was applied at
+struct InputEntry {
+    at_ms: u32,
+    action: Action,
+}
+
+// Actions of the current game, in the order they were applied. Movement is
+// applied at the start of a frame, so replaying the log with the same frame
+// times moves Labubu the same way.
+#[derive(Default)]
+pub struct InputLog {
+    entries: Vec<InputEntry>,
+}
+
+impl InputLog {
+    // Record an action at the given game time (seconds)
+    pub fn record(&mut self, elapsed_time: f64, action: Action) {
+        self.entries.push(InputEntry { at_ms: (elapsed_time * 1000.0) as u32, action });
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    // Actions as JSON: [{"atMs": 1200, "action": "move_left"}, {"atMs": 1500, "action": "move_to_lane", "lane": 3}, ...]
+    pub fn to_json(&self) -> String {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|entry| match entry.action {
+                Action::MoveToLane(lane) => {
+                    serde_json::json!({ "atMs": entry.at_ms, "action": entry.action.name(), "lane": lane })
+                }
+                action => serde_json::json!({ "atMs": entry.at_ms, "action": action.name() }),
+            })
+            .collect();
+        serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string())
+    }
+}
@@ -0,0 +1,264 @@
+use std::fmt;
+
+use crate::input::Action;
+
+// localStorage entry holding the player's key map
+const STORAGE_KEY: &str = "zklabubu.keyBindings";
+
+// Keys per action (primary and secondary)
+pub const SLOTS: usize = 2;
+
+// Escape opens the pause menu of the page, so it can't be rebound
+const RESERVED_KEYS: [&str; 1] = ["Escape"];
+
+// Actions a key can be bound to
+const BINDABLE_ACTIONS: [Action; 3] = [Action::MoveLeft, Action::MoveRight, Action::TogglePause];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyBindingError {
+    // The saved key map isn't a JSON object of key lists
+    Json(String),
+    UnknownAction(String),
+    NoSuchSlot(usize),
+    Reserved(String),
+    // The key already triggers another action (or the other slot of this one)
+    Conflict { key: String, action: Action },
+}
+
+impl fmt::Display for KeyBindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyBindingError::Json(err) => write!(f, "Invalid key map: {}", err),
+            KeyBindingError::UnknownAction(name) => write!(f, "Unknown action: {}", name),
+            KeyBindingError::NoSuchSlot(slot) => write!(f, "Key slot {} doesn't exist (0 to {})", slot, SLOTS - 1),
+            KeyBindingError::Reserved(key) => write!(f, "{} is reserved", key),
+            KeyBindingError::Conflict { key, action } => write!(f, "{} is already bound to {}", key, action.name()),
+        }
+    }
+}
+
+// Keyboard map from keys (`KeyboardEvent.key`) to actions. Letters are
+// stored lowercase, so a binding works with and without shift or caps lock.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    bindings: Vec<(Action, [Option<String>; SLOTS])>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let key = |key: &str| Some(key.to_string());
+        KeyBindings {
+            bindings: vec![
+                (Action::MoveLeft, [key("ArrowLeft"), key("a")]),
+                (Action::MoveRight, [key("ArrowRight"), key("d")]),
+                (Action::TogglePause, [key("p"), None]),
+            ],
+        }
+    }
+}
+
+impl KeyBindings {
+    // Action bound to a pressed key
+    pub fn action_for(&self, key: &str) -> Option<Action> {
+        let key = normalize(key);
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.iter().flatten().any(|bound| *bound == key))
+            .map(|(action, _)| *action)
+    }
+
+    // Bind `key` to a slot of an action (by name, e.g. "move_left")
+    pub fn set(&mut self, action_name: &str, slot: usize, key: &str) -> Result<(), KeyBindingError> {
+        let action = bindable_action(action_name)?;
+        if slot >= SLOTS {
+            return Err(KeyBindingError::NoSuchSlot(slot));
+        }
+        if RESERVED_KEYS.contains(&key) {
+            return Err(KeyBindingError::Reserved(key.to_string()));
+        }
+
+        let key = normalize(key);
+        for (bound_action, keys) in &self.bindings {
+            for (bound_slot, bound) in keys.iter().enumerate() {
+                let same_slot = *bound_action == action && bound_slot == slot;
+                if bound.as_deref() == Some(key.as_str()) && !same_slot {
+                    return Err(KeyBindingError::Conflict { key, action: *bound_action });
+                }
+            }
+        }
+
+        if let Some((_, keys)) = self.bindings.iter_mut().find(|(bound_action, _)| *bound_action == action) {
+            keys[slot] = Some(key);
+        }
+        Ok(())
+    }
+
+    // Remove the key of a slot
+    pub fn clear(&mut self, action_name: &str, slot: usize) -> Result<(), KeyBindingError> {
+        let action = bindable_action(action_name)?;
+        if slot >= SLOTS {
+            return Err(KeyBindingError::NoSuchSlot(slot));
+        }
+        if let Some((_, keys)) = self.bindings.iter_mut().find(|(bound_action, _)| *bound_action == action) {
+            keys[slot] = None;
+        }
+        Ok(())
+    }
+
+    // Key map as JSON: {"move_left": ["ArrowLeft", "a"], "toggle_pause": ["p", null], ...}
+    pub fn to_json(&self) -> String {
+        let map: serde_json::Map<_, _> = self
+            .bindings
+            .iter()
+            .map(|(action, keys)| (action.name().to_string(), serde_json::json!(keys)))
+            .collect();
+        serde_json::Value::Object(map).to_string()
+    }
+
+    // Parse a key map saved by `to_json`, checking it like `set` does. Actions
+    // missing from the map (added since it was saved) get their default keys,
+    // unless a saved binding already uses them.
+    pub fn from_json(json: &str) -> Result<Self, KeyBindingError> {
+        let saved: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(json).map_err(|err| KeyBindingError::Json(err.to_string()))?;
+
+        // Start from an empty map, so saved keys can't conflict with defaults
+        let mut bindings = KeyBindings {
+            bindings: BINDABLE_ACTIONS.iter().map(|action| (*action, Default::default())).collect(),
+        };
+        for (action_name, keys) in &saved {
+            bindable_action(action_name)?;
+            let keys = keys
+                .as_array()
+                .ok_or_else(|| KeyBindingError::Json(format!("keys of {} are not a list", action_name)))?;
+            for (slot, key) in keys.iter().enumerate() {
+                match key {
+                    serde_json::Value::String(key) => bindings.set(action_name, slot, key)?,
+                    serde_json::Value::Null => {}
+                    _ => return Err(KeyBindingError::Json(format!("key of {} is not a string", action_name))),
+                }
+            }
+        }
+
+        for (action, keys) in &KeyBindings::default().bindings {
+            if saved.contains_key(action.name()) {
+                continue;
+            }
+            for (slot, key) in keys.iter().enumerate() {
+                if let Some(key) = key {
+                    // A saved binding took this key: the slot stays empty
+                    let _ = bindings.set(action.name(), slot, key);
+                }
+            }
+        }
+        Ok(bindings)
+    }
+
+    // Saved key map, or the defaults when none is saved or it can't be parsed
+    pub fn load() -> Self {
+        storage()
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .and_then(|json| KeyBindings::from_json(&json).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Some(storage) = storage() {
+            let _ = storage.set_item(STORAGE_KEY, &self.to_json());
+        }
+    }
+
+    // Back to the defaults, forgetting the saved map
+    pub fn reset(&mut self) {
+        *self = KeyBindings::default();
+        if let Some(storage) = storage() {
+            let _ = storage.remove_item(STORAGE_KEY);
+        }
+    }
+}
+
+fn bindable_action(name: &str) -> Result<Action, KeyBindingError> {
+    BINDABLE_ACTIONS
+        .into_iter()
+        .find(|action| action.name() == name)
+        .ok_or_else(|| KeyBindingError::UnknownAction(name.to_string()))
+}
+
+// Single characters are matched case-insensitively, named keys as they are
+fn normalize(key: &str) -> String {
+    if key.chars().count() == 1 {
+        key.to_lowercase()
+    } else {
+        key.to_string()
+    }
+}
+
+// localStorage, if the browser allows it
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_rejects_a_key_bound_elsewhere() {
+        let mut bindings = KeyBindings::default();
+        assert_eq!(
+            bindings.set("move_right", 0, "ArrowLeft"),
+            Err(KeyBindingError::Conflict { key: "ArrowLeft".to_string(), action: Action::MoveLeft })
+        );
+        // The other slot of the same action counts too
+        assert_eq!(
+            bindings.set("move_left", 1, "ArrowLeft"),
+            Err(KeyBindingError::Conflict { key: "ArrowLeft".to_string(), action: Action::MoveLeft })
+        );
+        // Letters conflict regardless of case
+        assert!(matches!(bindings.set("toggle_pause", 1, "A"), Err(KeyBindingError::Conflict { .. })));
+    }
+
+    #[test]
+    fn set_rebinds_a_slot() {
+        let mut bindings = KeyBindings::default();
+        bindings.set("move_left", 0, "ArrowLeft").unwrap();
+        bindings.set("move_left", 1, "Q").unwrap();
+        assert_eq!(bindings.action_for("q"), Some(Action::MoveLeft));
+        assert_eq!(bindings.action_for("a"), None);
+    }
+
+    #[test]
+    fn set_rejects_reserved_keys_and_bad_slots() {
+        let mut bindings = KeyBindings::default();
+        assert_eq!(bindings.set("move_left", 0, "Escape"), Err(KeyBindingError::Reserved("Escape".to_string())));
+        assert_eq!(bindings.set("move_left", SLOTS, "q"), Err(KeyBindingError::NoSuchSlot(SLOTS)));
+        assert_eq!(bindings.set("jump", 0, "q"), Err(KeyBindingError::UnknownAction("jump".to_string())));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let mut bindings = KeyBindings::default();
+        bindings.set("toggle_pause", 1, " ").unwrap();
+        bindings.clear("move_right", 1).unwrap();
+        assert_eq!(KeyBindings::from_json(&bindings.to_json()), Ok(bindings));
+    }
+
+    #[test]
+    fn from_json_rejects_malformed_maps() {
+        for json in ["", "not json", "[]", r#"{"move_left": "a"}"#, r#"{"move_left": [1]}"#] {
+            assert!(matches!(KeyBindings::from_json(json), Err(KeyBindingError::Json(_))), "{}", json);
+        }
+        assert!(matches!(
+            KeyBindings::from_json(r#"{"move_left": ["a"], "move_right": ["a"]}"#),
+            Err(KeyBindingError::Conflict { .. })
+        ));
+    }
+
+    #[test]
+    fn from_json_fills_missing_actions_with_defaults() {
+        let bindings = KeyBindings::from_json(r#"{"move_left": ["ArrowLeft", "d"]}"#).unwrap();
+        assert_eq!(bindings.action_for("d"), Some(Action::MoveLeft));
+        assert_eq!(bindings.action_for("ArrowRight"), Some(Action::MoveRight));
+        assert_eq!(bindings.action_for("p"), Some(Action::TogglePause));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::utils::Rng;
+
+// Lane geometry of the board: items fall in lanes and Labubu moves between them
+#[derive(Clone, Debug, PartialEq)]
+pub struct LaneLayout {
+    count: usize,
+    lane_width: f64,
+}
+
+impl LaneLayout {
+    // Split the canvas into `count` lanes of equal width
+    pub fn new(count: usize, canvas_width: f64) -> Self {
+        LaneLayout {
+            count,
+            lane_width: canvas_width / count as f64,
+        }
+    }
+
+    // Number of lanes
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // Lane Labubu starts in (left of the center for an even count)
+    pub fn middle(&self) -> usize {
+        (self.count - 1) / 2
+    }
+
+    // Size of a sprite, shrunk to fit narrow lanes
+    pub fn fit(&self, size: f64) -> f64 {
+        size.min(self.lane_width)
+    }
+
+    // X position of a sprite of `width` centered in `lane`
+    pub fn x(&self, lane: usize, width: f64) -> f64 {
+        (lane as f64 * self.lane_width) + (self.lane_width - width) / 2.0
+    }
+
+    // Lane under the canvas position `x`
+    pub fn lane_at(&self, x: f64) -> usize {
+        ((x / self.lane_width).max(0.0) as usize).min(self.count - 1)
+    }
+
+    // Random lane
+    pub fn random_lane(&self, rng: &mut Rng) -> usize {
+        rng.range(0.0, self.count as f64) as usize
+    }
+}
@@ -0,0 +1,51 @@
+use zklabubu_proof_lib::{GameEvent, GameEventKind, LedgerCounts, LedgerReplay, ACHIEVEMENTS};
+
+// Events of the current game. The ledger is sent with the proof request and
+// replayed in the zkVM to prove achievements, and replayed live here to show
+// achievement progress.
+#[derive(Default)]
+pub struct EventLedger {
+    events: Vec<GameEvent>,
+    replay: LedgerReplay,
+}
+
+impl EventLedger {
+    // Record an event at the given game time (seconds)
+    pub fn record(&mut self, elapsed_time: f64, kind: GameEventKind) {
+        let event = GameEvent { at_ms: (elapsed_time * 1000.0) as u32, kind };
+        // Game time only moves forward, so the replay never rejects an event
+        let _ = self.replay.record(&event);
+        self.events.push(event);
+    }
+
+    pub fn clear(&mut self) {
+        *self = EventLedger::default();
+    }
+
+    pub fn counts(&self) -> &LedgerCounts {
+        &self.replay.counts
+    }
+
+    // Events as JSON (sent with the proof request)
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.events).unwrap_or_else(|_| "[]".to_string())
+    }
+
+    // Progress of every achievement as JSON, for the UI (as the zkVM sees it, up to the last event)
+    pub fn progress_json(&self) -> String {
+        let unlocked = self.replay.unlocked();
+        let progress: Vec<_> = ACHIEVEMENTS
+            .iter()
+            .map(|achievement| {
+                serde_json::json!({
+                    "name": achievement.name,
+                    "description": achievement.description,
+                    "progress": self.replay.progress(achievement),
+                    "target": achievement.condition.target(),
+                    "unlocked": unlocked & (1 << achievement.bit) != 0,
+                })
+            })
+            .collect();
+        serde_json::to_string(&progress).unwrap_or_else(|_| "[]".to_string())
+    }
+}
@@ -1,9 +1,23 @@
 mod entities;
+mod focus;
 mod game;
+mod game_loop;
+mod gamepad;
+mod hud;
+mod input;
+mod keybindings;
+mod lanes;
+mod ledger;
+mod pointer;
+mod proof_stream;
+mod session_ticket;
 mod utils;
 
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::Rc;
+
 use wasm_bindgen::prelude::*;
-use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, KeyboardEvent, HtmlImageElement, HtmlAudioElement, Document};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, KeyboardEvent, PointerEvent, TouchEvent, HtmlImageElement, HtmlAudioElement, Document};
 
 
 // For debugging in case of panic
@@ -24,14 +38,60 @@ pub enum GameState {
     GameOver,
 }
 
+// What a frame updates, shared with the animation loop of `GameManager::run`
+struct Session {
+    game: game::Game,
+    game_time: u32,    // Game time (seconds)
+    focus: focus::FocusWatcher, // Tab hidden or window blurred
+    auto_paused: bool, // Paused by losing focus, not yet reported to the page
+}
+
+impl Session {
+    // Update and draw one frame, returns true when the game is over
+    fn update(&mut self, delta_time: f64) -> bool {
+        let playing = self.game.get_state() == game::GameState::Playing;
+        
+        // Focus was lost: pause, and drop this frame (its delta spans the time away)
+        if self.focus.take_lost() && playing {
+            self.game.stop();
+            self.auto_paused = true;
+            return self.game.is_game_over();
+        }
+        
+        // Update the game
+        let game_over = self.game.update(delta_time);
+        
+        // Game time includes this frame, so it covers every event in the ledger
+        if playing {
+            self.game_time = self.game.get_elapsed_time() as u32;
+        }
+        
+        game_over
+    }
+    
+    // A new game begins: reset the game time and forget earlier focus loss
+    fn begin(&mut self) {
+        self.focus.take_lost();
+        
+        // Reset game time (egg counts come from the event ledger)
+        self.game_time = 0;
+    }
+}
+
 // Main function called from JavaScript
 #[wasm_bindgen]
 pub struct GameManager {
-    game: game::Game,
-    yellow_eggs: u32, // Number of collected yellow eggs
-    blue_eggs: u32,   // Number of collected blue eggs
-    purple_eggs: u32, // Number of collected purple eggs
-    game_time: u32,    // Game time (seconds)
+    session: Rc<RefCell<Session>>,
+    animation: Option<game_loop::AnimationLoop>, // Frame loop of `run` (None until first run)
+    on_frame: Rc<RefCell<Option<js_sys::Function>>>, // Page hook called after each frame of `run`
+    proof_stream: Option<proof_stream::ProofLogStream>, // Live proof log subscription
+    session_ticket: Option<session_ticket::SessionTicket>, // Ticket backing the current game
+    challenge_date: Option<u32>, // Day of the daily challenge being played (YYYYMMDD)
+    config: zklabubu_proof_lib::GameConfig, // Game balance before the difficulty preset
+    difficulty: zklabubu_proof_lib::Difficulty, // Difficulty preset of the current game (committed by the proof)
+    pending_difficulty: Option<zklabubu_proof_lib::Difficulty>, // Applied with its config on the next restart
+    gamepad: gamepad::GamepadInput, // Polled every frame
+    key_bindings: keybindings::KeyBindings, // Keyboard map (saved in localStorage)
 }
 
 #[wasm_bindgen]
@@ -54,9 +114,17 @@ impl GameManager {
         egg_sound: HtmlAudioElement,
         rock_sound: HtmlAudioElement,
         shield_hit_sound: HtmlAudioElement,    // Shield hit sound
-    ) -> GameManager {
+        config_json: Option<String>,           // Game balance (defaults when missing)
+    ) -> Result<GameManager, JsValue> {
         utils::set_panic_hook();
         
+        // The config's hash is committed by the proof, so a bad one is an error
+        let config = match config_json {
+            Some(json) => zklabubu_proof_lib::GameConfig::from_json(&json)
+                .map_err(|err| JsValue::from_str(&err.to_string()))?,
+            None => zklabubu_proof_lib::GameConfig::default(),
+        };
+        
         let context = canvas
             .get_context("2d")
             .unwrap()
@@ -82,97 +150,415 @@ impl GameManager {
             egg_sound,
             rock_sound,
             shield_hit_sound,     // Add shield hit sound
+            config.clone(),
         );
         
-        GameManager { 
+        let session = Session {
             game,
-            yellow_eggs: 0,
-            blue_eggs: 0,
-            purple_eggs: 0,
-            game_time: 0
-        }
+            game_time: 0,
+            focus: focus::FocusWatcher::new()?,
+            auto_paused: false,
+        };
+        
+        Ok(GameManager { 
+            session: Rc::new(RefCell::new(session)),
+            animation: None,
+            on_frame: Rc::new(RefCell::new(None)),
+            proof_stream: None,
+            session_ticket: None,
+            challenge_date: None,
+            config,
+            difficulty: zklabubu_proof_lib::Difficulty::Normal,
+            pending_difficulty: None,
+            gamepad: gamepad::GamepadInput::default(),
+            key_bindings: keybindings::KeyBindings::load(),
+        })
     }
     
     // Process keyboard input
     #[wasm_bindgen]
     pub fn handle_key_press(&mut self, event: KeyboardEvent) {
-        self.game.handle_key_press(event);
+        if let Some(action) = self.key_bindings.action_for(&event.key()) {
+            self.game_mut().handle_action(action);
+        }
+    }
+    
+    // Get the key map (JSON: {"move_left": ["ArrowLeft", "a"], "move_right": [...], "toggle_pause": [...]})
+    #[wasm_bindgen]
+    pub fn get_key_bindings(&self) -> String {
+        self.key_bindings.to_json()
+    }
+    
+    // Bind a key (`KeyboardEvent.key`) to a slot (0 or 1) of an action, and save the map.
+    // Fails if the key is reserved or already bound elsewhere.
+    #[wasm_bindgen]
+    pub fn set_key_binding(&mut self, action: &str, slot: usize, key: &str) -> Result<(), JsValue> {
+        self.key_bindings
+            .set(action, slot, key)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        self.key_bindings.save();
+        Ok(())
+    }
+    
+    // Unbind a slot of an action, and save the map
+    #[wasm_bindgen]
+    pub fn clear_key_binding(&mut self, action: &str, slot: usize) -> Result<(), JsValue> {
+        self.key_bindings
+            .clear(action, slot)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        self.key_bindings.save();
+        Ok(())
+    }
+    
+    // Restore the default key map
+    #[wasm_bindgen]
+    pub fn reset_key_bindings(&mut self) {
+        self.key_bindings.reset();
+    }
+    
+    // Process a pointer press on the canvas (mouse, pen or touch)
+    #[wasm_bindgen]
+    pub fn handle_pointer_down(&mut self, event: PointerEvent) {
+        if let Some((x, y)) = pointer::pointer_position(&event) {
+            self.game_mut().handle_pointer_down(x, y);
+        }
+    }
+    
+    // Process the end of a pointer press (tap or swipe)
+    #[wasm_bindgen]
+    pub fn handle_pointer_up(&mut self, event: PointerEvent) {
+        match pointer::pointer_position(&event) {
+            Some((x, y)) => self.game_mut().handle_pointer_up(x, y),
+            None => self.game_mut().cancel_pointer(),
+        }
+    }
+    
+    // Process a cancelled pointer or touch press
+    #[wasm_bindgen]
+    pub fn handle_pointer_cancel(&mut self) {
+        self.game_mut().cancel_pointer();
+    }
+    
+    // Process a touch start on the canvas (browsers without pointer events)
+    #[wasm_bindgen]
+    pub fn handle_touch_start(&mut self, event: TouchEvent) {
+        if let Some((x, y)) = pointer::touch_position(&event) {
+            self.game_mut().handle_pointer_down(x, y);
+        }
+    }
+    
+    // Process a touch end (tap or swipe)
+    #[wasm_bindgen]
+    pub fn handle_touch_end(&mut self, event: TouchEvent) {
+        match pointer::touch_position(&event) {
+            Some((x, y)) => self.game_mut().handle_pointer_up(x, y),
+            None => self.game_mut().cancel_pointer(),
+        }
+    }
+    
+    // Poll the connected gamepads (call once per frame, also while paused).
+    // Movement is applied to the game; returns true when start was pressed,
+    // so the page can open or close its pause menu.
+    #[wasm_bindgen]
+    pub fn poll_gamepads(&mut self) -> bool {
+        let mut toggle_pause = false;
+        for action in self.gamepad.poll() {
+            match action {
+                input::Action::TogglePause => toggle_pause = true,
+                action => self.game_mut().handle_action(action),
+            }
+        }
+        toggle_pause
+    }
+    
+    // Set the gamepad stick deadzone (0.05 to 0.95 of the full tilt)
+    #[wasm_bindgen]
+    pub fn set_gamepad_deadzone(&mut self, deadzone: f64) {
+        self.gamepad.set_deadzone(deadzone);
+    }
+    
+    // Get the gamepad stick deadzone
+    #[wasm_bindgen]
+    pub fn get_gamepad_deadzone(&self) -> f64 {
+        self.gamepad.deadzone()
+    }
+    
+    // Choose what a tap does: "halves" (left or right half moves one lane) or "lanes" (move to the tapped lane)
+    #[wasm_bindgen]
+    pub fn set_tap_mode(&mut self, mode: &str) -> Result<(), JsValue> {
+        let tap_mode = pointer::TapMode::from_name(mode)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown tap mode: {}", mode)))?;
+        self.game_mut().set_tap_mode(tap_mode);
+        Ok(())
     }
     
     // Start the game
     #[wasm_bindgen]
     pub fn start(&mut self) {
-        self.game.start();
-        
-        // Reset egg counters
-        self.yellow_eggs = 0;
-        self.blue_eggs = 0;
-        self.purple_eggs = 0;
-        self.game_time = 0;
+        self.game_mut().start();
+        self.session.borrow_mut().begin();
     }
     
     // Stop the game
     #[wasm_bindgen]
     pub fn stop(&mut self) {
-        self.game.stop();
+        self.game_mut().stop();
+    }
+    
+    // Restart the game with the pending config and difficulty, if any
+    fn restart_game(&mut self) {
+        if let Some(difficulty) = self.pending_difficulty.take() {
+            self.difficulty = difficulty;
+        }
+        self.game_mut().restart();
     }
     
     // Restart the game
     #[wasm_bindgen]
     pub fn restart(&mut self) {
-        self.game.restart();
+        // A ticket backs exactly one game, a restart gets a fresh sequence
+        self.session_ticket = None;
+        self.challenge_date = None;
+        self.game_mut().set_seed(utils::Rng::from_entropy().next_u64());
+        self.restart_game();
+        self.session.borrow_mut().begin();
+    }
+    
+    // Start a new game from a session ticket issued by the proving service
+    #[wasm_bindgen]
+    pub fn start_with_ticket(&mut self, ticket_json: &str) -> Result<(), JsValue> {
+        let ticket = session_ticket::SessionTicket::parse(ticket_json)?;
+        if ticket.is_expired(js_sys::Date::now() / 1000.0) {
+            return Err(JsValue::from_str("Session ticket expired"));
+        }
         
-        // Reset egg counters
-        self.yellow_eggs = 0;
-        self.blue_eggs = 0;
-        self.purple_eggs = 0;
-        self.game_time = 0;
+        // The ticket seed drives the spawn sequence
+        self.game_mut().set_seed(ticket.seed);
+        self.restart_game();
+        self.session.borrow_mut().begin();
+        self.challenge_date = ticket.challenge_date;
+        self.session_ticket = Some(ticket);
+        
+        utils::log("Game started from session ticket");
+        Ok(())
+    }
+    
+    // Start today's daily challenge without a ticket (`date` is the UTC day, YYYY-MM-DD).
+    // The seed is the public one, so the run can't be proved as a daily challenge:
+    // use a daily ticket from the proving service for that.
+    #[wasm_bindgen]
+    pub fn start_daily(&mut self, date: &str) -> Result<(), JsValue> {
+        let challenge_date = zklabubu_proof_lib::parse_challenge_date(date)
+            .ok_or_else(|| JsValue::from_str("Daily challenge date must be YYYY-MM-DD"))?;
+        
+        self.session_ticket = None;
+        self.game_mut().set_seed(zklabubu_proof_lib::daily_seed(date, &[]));
+        self.restart_game();
+        self.session.borrow_mut().begin();
+        self.challenge_date = Some(challenge_date);
+        
+        utils::log(&format!("Daily challenge {} started", date));
+        Ok(())
+    }
+    
+    // Get the day of the daily challenge being played (YYYY-MM-DD)
+    #[wasm_bindgen]
+    pub fn get_challenge_date(&self) -> Option<String> {
+        self.challenge_date.map(zklabubu_proof_lib::format_challenge_date)
+    }
+    
+    // Get the session ticket of the current game (sent with the proof request)
+    #[wasm_bindgen]
+    pub fn get_session_ticket(&self) -> Option<String> {
+        self.session_ticket.as_ref().map(|ticket| ticket.json().to_string())
+    }
+    
+    // Get the game config (JSON, sent with the proof request with the difficulty)
+    #[wasm_bindgen]
+    pub fn get_config(&self) -> String {
+        serde_json::to_string(&self.config).unwrap_or_default()
+    }
+    
+    // Choose the difficulty preset: easy, normal, hard or nightmare (applies from the next restart)
+    #[wasm_bindgen]
+    pub fn set_difficulty(&mut self, name: &str) -> Result<(), JsValue> {
+        let difficulty = zklabubu_proof_lib::Difficulty::from_name(name)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown difficulty: {}", name)))?;
+        
+        // The preset must leave a playable config
+        let config = difficulty.apply(&self.config);
+        config.validate().map_err(|err| JsValue::from_str(&err.to_string()))?;
+        
+        self.game_mut().set_config(config);
+        self.pending_difficulty = Some(difficulty);
+        utils::log(&format!("Difficulty set to {} (from the next game)", difficulty.name()));
+        Ok(())
+    }
+    
+    // Get the difficulty preset
+    #[wasm_bindgen]
+    pub fn get_difficulty(&self) -> String {
+        self.difficulty.name().to_string()
+    }
+    
+    // Get the rules hash of the game config with the difficulty preset (hex, committed by the proof)
+    #[wasm_bindgen]
+    pub fn get_rules_hash(&self) -> String {
+        self.game().get_config().rules_hash().to_string()
+    }
+    
+    // Get the seed of the current spawn sequence (hex)
+    #[wasm_bindgen]
+    pub fn get_seed(&self) -> String {
+        format!("0x{:016x}", self.game().get_seed())
     }
     
     // Update and draw the game
     #[wasm_bindgen]
     pub fn update(&mut self, delta_time: f64) -> bool {
-        // Increase game time (1 second = 1000 ms)
-        if self.game.get_state() == game::GameState::Playing {
-            self.game_time = self.game.get_elapsed_time() as u32;
-        }
+        self.session.borrow_mut().update(delta_time)
+    }
+    
+    // Drive the game from a Rust `requestAnimationFrame` loop instead of calling
+    // `update` from the page. Frame times come from `performance.now()`;
+    // `on_frame(gameOver)` is called after each frame to refresh the page.
+    #[wasm_bindgen]
+    pub fn run(&mut self, on_frame: Option<js_sys::Function>) -> Result<(), JsValue> {
+        *self.on_frame.borrow_mut() = on_frame;
         
-        // Check for newly collected eggs
-        if let Some(item_type) = self.game.get_last_collected_item() {
-            match item_type {
-                entities::FallingItemType::YellowEgg => self.yellow_eggs += 1,
-                entities::FallingItemType::PinkEgg => self.blue_eggs += 1,
-                entities::FallingItemType::PurpleEgg => self.purple_eggs += 1,
-                _ => {} // Don't process other items
-            }
+        if self.animation.is_none() {
+            let session = self.session.clone();
+            let on_frame = self.on_frame.clone();
+            self.animation = Some(game_loop::AnimationLoop::new(move |delta_time| {
+                let game_over = session.borrow_mut().update(delta_time);
+                
+                // The session is released first, so the hook can call back into the manager
+                let hook = on_frame.borrow().clone();
+                if let Some(hook) = hook {
+                    if let Err(err) = hook.call1(&JsValue::NULL, &JsValue::from_bool(game_over)) {
+                        utils::log(&format!("Frame hook failed: {:?}", err));
+                    }
+                }
+            })?);
+        }
+        if let Some(animation) = &self.animation {
+            animation.start();
+        }
+        Ok(())
+    }
+    
+    // Stop the loop started by `run` (the game state is left as it is)
+    #[wasm_bindgen]
+    pub fn stop_loop(&mut self) {
+        if let Some(animation) = &self.animation {
+            animation.stop();
         }
-        
-        // Update the game
-        self.game.update(delta_time)
+    }
+    
+    // Check whether the loop started by `run` is running
+    #[wasm_bindgen]
+    pub fn is_running(&self) -> bool {
+        self.animation.as_ref().is_some_and(|animation| animation.is_running())
+    }
+    
+    // Get the frame rate of the loop started by `run` (0 when stopped)
+    #[wasm_bindgen]
+    pub fn get_fps(&self) -> f64 {
+        self.animation.as_ref().map_or(0.0, |animation| animation.fps())
+    }
+    
+    // Check whether the game paused itself because the tab was hidden or the
+    // window lost focus (reported once, so the page can show its resume overlay)
+    #[wasm_bindgen]
+    pub fn take_auto_paused(&mut self) -> bool {
+        std::mem::take(&mut self.session.borrow_mut().auto_paused)
     }
     
     // Get the current score
     #[wasm_bindgen]
     pub fn get_score(&self) -> u32 {
-        self.game.get_score()
+        self.game().get_score()
+    }
+    
+    // Get the score shown to the player (includes the double-points bonus)
+    #[wasm_bindgen]
+    pub fn get_visual_score(&self) -> u32 {
+        self.game().get_visual_score()
+    }
+    
+    // Get the difficulty level (from 1)
+    #[wasm_bindgen]
+    pub fn get_level(&self) -> u32 {
+        self.game().get_level()
+    }
+    
+    // Get the eggs caught in a row (ended by a rock hit or a missed egg)
+    #[wasm_bindgen]
+    pub fn get_combo(&self) -> u32 {
+        self.game().get_combo()
+    }
+    
+    // Choose who draws the score, lives, level, combo and egg counters:
+    // "canvas" (the game) or "overlay" (the page, from the getters)
+    #[wasm_bindgen]
+    pub fn set_hud_mode(&mut self, mode: &str) -> Result<(), JsValue> {
+        let hud_mode = hud::HudMode::from_name(mode)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown HUD mode: {}", mode)))?;
+        self.game_mut().hud_mut().mode = hud_mode;
+        Ok(())
+    }
+    
+    // Move or hide HUD elements (JSON: {"score": "top_left", "lives": "top_right", "combo": "hidden",
+    // "fontSize": 20, "margin": 12}; elements are score, level, combo, powerUps, lives and eggs)
+    #[wasm_bindgen]
+    pub fn set_hud_layout(&mut self, layout_json: &str) -> Result<(), JsValue> {
+        self.game_mut()
+            .hud_mut()
+            .layout
+            .update_from_json(layout_json)
+            .map_err(|err| JsValue::from_str(&err.to_string()))
+    }
+    
+    // Get the HUD layout (JSON, as read by `set_hud_layout`)
+    #[wasm_bindgen]
+    pub fn get_hud_layout(&self) -> String {
+        self.game().hud().layout.to_json()
     }
     
     // Get the remaining lives
     #[wasm_bindgen]
     pub fn get_lives(&self) -> u32 {
-        self.game.get_lives()
+        self.game().get_lives()
+    }
+    
+    // Get the rocks that cost a life
+    #[wasm_bindgen]
+    pub fn get_rock_hits(&self) -> u32 {
+        self.game().get_rock_hits()
+    }
+    
+    // Get the rocks blocked by the shield
+    #[wasm_bindgen]
+    pub fn get_shield_blocks(&self) -> u32 {
+        self.game().get_shield_blocks()
+    }
+    
+    // Get the extra lives gained
+    #[wasm_bindgen]
+    pub fn get_extra_lives(&self) -> u32 {
+        self.game().get_extra_lives()
     }
     
     // Check if the game is over
     #[wasm_bindgen]
     pub fn is_game_over(&self) -> bool {
-        self.game.is_game_over()
+        self.game().is_game_over()
     }
     
     // Get the game state (exposed to JavaScript)
     #[wasm_bindgen]
     pub fn get_game_state(&self) -> GameState {
-        match self.game.get_state() {
+        match self.game().get_state() {
             game::GameState::NotStarted => GameState::NotStarted,
             game::GameState::Playing => GameState::Playing,
             game::GameState::Paused => GameState::Paused,
@@ -184,32 +570,50 @@ impl GameManager {
     #[wasm_bindgen]
     pub fn set_sound_enabled(&mut self, enabled: bool) {
         // Pass sound state to the Game object
-        self.game.set_sound_enabled(enabled);
+        self.game_mut().set_sound_enabled(enabled);
         utils::log(&format!("Sound status: {}", if enabled { "on" } else { "off" }));
     }
     
     // Get the number of collected yellow eggs
     #[wasm_bindgen]
     pub fn get_yellow_eggs_count(&self) -> u32 {
-        self.yellow_eggs
+        self.game().get_ledger_counts().yellow_eggs
     }
     
     // Get the number of collected pink eggs
     #[wasm_bindgen]
     pub fn get_blue_eggs_count(&self) -> u32 {
-        self.blue_eggs
+        self.game().get_ledger_counts().pink_eggs
     }
     
     // Get the number of collected purple eggs
     #[wasm_bindgen]
     pub fn get_purple_eggs_count(&self) -> u32 {
-        self.purple_eggs
+        self.game().get_ledger_counts().purple_eggs
+    }
+    
+    // Get the event ledger of the game (JSON, sent with the proof request)
+    #[wasm_bindgen]
+    pub fn get_event_ledger(&self) -> String {
+        self.game().get_event_ledger()
+    }
+    
+    // Get the actions applied this game (JSON, with their game time)
+    #[wasm_bindgen]
+    pub fn get_input_log(&self) -> String {
+        self.game().get_input_log()
+    }
+    
+    // Get the progress of every achievement (JSON)
+    #[wasm_bindgen]
+    pub fn get_achievement_progress(&self) -> String {
+        self.game().get_achievement_progress()
     }
     
     // Get the game time
     #[wasm_bindgen]
     pub fn get_game_time(&self) -> u32 {
-        self.game_time
+        self.session.borrow().game_time
     }
     
     // Show SP1 Proof interface
@@ -231,7 +635,10 @@ impl GameManager {
     
     // Hide SP1 proof interface
     #[wasm_bindgen]
-    pub fn hide_sp1_proof_interface(&self) -> Result<(), JsValue> {
+    pub fn hide_sp1_proof_interface(&mut self) -> Result<(), JsValue> {
+        // Stop listening to the proving service
+        self.stop_proof_log_stream();
+        
         let window = web_sys::window().expect("no global window");
         let document = window.document().expect("no global document");
         
@@ -248,6 +655,26 @@ impl GameManager {
         Ok(())
     }
     
+    // Stream live proof logs of a job into the SP1 terminal
+    #[wasm_bindgen]
+    pub fn stream_proof_log(&mut self, url: &str) -> Result<(), JsValue> {
+        // Only one job is followed at a time
+        self.stop_proof_log_stream();
+        
+        self.proof_stream = Some(proof_stream::ProofLogStream::connect(url)?);
+        utils::log(&format!("Streaming proof log from {}", url));
+        
+        Ok(())
+    }
+    
+    // Stop streaming proof logs
+    #[wasm_bindgen]
+    pub fn stop_proof_log_stream(&mut self) {
+        if let Some(stream) = self.proof_stream.take() {
+            stream.close();
+        }
+    }
+    
     // Create SP1 Terminal interface
     fn create_sp1_terminal(&self, document: &Document) -> Result<(), JsValue> {
         // Remove existing terminal if present
@@ -353,9 +780,86 @@ impl GameManager {
     }
 }
 
+impl GameManager {
+    fn game(&self) -> Ref<'_, game::Game> {
+        Ref::map(self.session.borrow(), |session| &session.game)
+    }
+    
+    fn game_mut(&self) -> RefMut<'_, game::Game> {
+        RefMut::map(self.session.borrow_mut(), |session| &mut session.game)
+    }
+}
+
+// Proof log line levels (each one maps to a terminal colour)
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ProofLogLevel {
+    Info,
+    Progress,
+    Success,
+    Error,
+}
+
+impl ProofLogLevel {
+    // Guess the level from the message text (used for plain log messages)
+    pub(crate) fn from_message(message: &str) -> ProofLogLevel {
+        if message.contains("error") || message.contains("failed") || message.contains("ERROR") {
+            ProofLogLevel::Error
+        } else if message.contains("success") || message.contains("verified") || message.contains("SUCCESS") {
+            ProofLogLevel::Success
+        } else if message.contains("generating") || message.contains("wait") {
+            ProofLogLevel::Progress
+        } else {
+            ProofLogLevel::Info
+        }
+    }
+    
+    // Parse the level name sent by the proving service
+    pub(crate) fn from_name(name: &str) -> Option<ProofLogLevel> {
+        match name {
+            "info" => Some(ProofLogLevel::Info),
+            "progress" | "stage" => Some(ProofLogLevel::Progress),
+            "success" => Some(ProofLogLevel::Success),
+            "error" => Some(ProofLogLevel::Error),
+            _ => None,
+        }
+    }
+    
+    // CSS class of the terminal line
+    fn class_name(&self) -> &'static str {
+        match self {
+            ProofLogLevel::Info => "proof-log-line proof-log-info",
+            ProofLogLevel::Progress => "proof-log-line proof-log-progress",
+            ProofLogLevel::Success => "proof-log-line proof-log-success",
+            ProofLogLevel::Error => "proof-log-line proof-log-error",
+        }
+    }
+    
+    // Inline colour of the terminal line
+    fn color(&self) -> Option<&'static str> {
+        match self {
+            ProofLogLevel::Info => None,
+            ProofLogLevel::Progress => Some("#f39c12"), // Orange
+            ProofLogLevel::Success => Some("#2ecc71"), // Green
+            ProofLogLevel::Error => Some("#e74c3c"),   // Red
+        }
+    }
+}
+
 // Add log message to proof area
 #[wasm_bindgen]
 pub fn log_to_proof_area(message: &str) {
+    append_proof_log_line(message, ProofLogLevel::from_message(message));
+}
+
+// Add log message to proof area with an explicit level
+#[wasm_bindgen]
+pub fn log_to_proof_area_with_level(message: &str, level: ProofLogLevel) {
+    append_proof_log_line(message, level);
+}
+
+// Append a timestamped, coloured line to the proof terminal
+pub(crate) fn append_proof_log_line(message: &str, level: ProofLogLevel) {
     let window = web_sys::window().expect("No global window");
     let document = window.document().expect("No global document");
     
@@ -371,21 +875,18 @@ pub fn log_to_proof_area(message: &str) {
         // Create new line
         if let Ok(line_element) = document.create_element("div") {
             line_element.set_text_content(Some(&format!("{}{}", timestamp, message)));
+            line_element.set_attribute("class", level.class_name()).ok();
             
             // Set line color
-            if message.contains("error") || message.contains("failed") || message.contains("ERROR") {
-                line_element.set_attribute("style", "color: #e74c3c;").ok(); // Red
-            } else if message.contains("success") || message.contains("verified") || message.contains("SUCCESS") {
-                line_element.set_attribute("style", "color: #2ecc71;").ok(); // Green
-            } else if message.contains("generating") || message.contains("wait") {
-                line_element.set_attribute("style", "color: #f39c12;").ok(); // Orange
+            if let Some(color) = level.color() {
+                line_element.set_attribute("style", &format!("color: {};", color)).ok();
             }
             
             // Add line to log area
             proof_log.append_child(&line_element).ok();
             
             // Auto-scroll
-            let _ = js_sys::eval(&format!("document.getElementById('proof-log').scrollTop = document.getElementById('proof-log').scrollHeight"));
+            proof_log.set_scroll_top(proof_log.scroll_height());
         }
     }
 }
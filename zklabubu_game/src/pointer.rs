@@ -0,0 +1,90 @@
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, HtmlCanvasElement, MouseEvent, TouchEvent};
+
+use crate::input::Action;
+use crate::lanes::LaneLayout;
+
+// Shortest horizontal drag that counts as a swipe (canvas pixels)
+const SWIPE_MIN_DISTANCE: f64 = 30.0;
+
+// What a tap does
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TapMode {
+    #[default]
+    Halves, // Tap the left or right half to move one lane
+    Lanes,  // Tap a lane to move straight there
+}
+
+impl TapMode {
+    pub fn from_name(name: &str) -> Option<TapMode> {
+        match name {
+            "halves" => Some(TapMode::Halves),
+            "lanes" => Some(TapMode::Lanes),
+            _ => None,
+        }
+    }
+}
+
+// Turns pointer and touch gestures on the canvas into actions: a horizontal
+// swipe moves one lane, a tap moves as the tap mode says
+#[derive(Default)]
+pub struct PointerTracker {
+    start: Option<(f64, f64)>, // Where the current press started (canvas pixels)
+    pub tap_mode: TapMode,
+}
+
+impl PointerTracker {
+    // A finger or button went down
+    pub fn press(&mut self, x: f64, y: f64) {
+        self.start = Some((x, y));
+    }
+
+    // The press ended: a swipe or a tap
+    pub fn release(&mut self, x: f64, y: f64, lanes: &LaneLayout, canvas_width: f64) -> Option<Action> {
+        let (start_x, start_y) = self.start.take()?;
+        let (dx, dy) = (x - start_x, y - start_y);
+
+        // Mostly horizontal drag: swipe
+        if dx.abs() >= SWIPE_MIN_DISTANCE && dx.abs() > dy.abs() {
+            return Some(if dx < 0.0 { Action::MoveLeft } else { Action::MoveRight });
+        }
+        // Any other long drag is not a tap
+        if dx.abs() >= SWIPE_MIN_DISTANCE || dy.abs() >= SWIPE_MIN_DISTANCE {
+            return None;
+        }
+
+        Some(match self.tap_mode {
+            TapMode::Halves if start_x < canvas_width / 2.0 => Action::MoveLeft,
+            TapMode::Halves => Action::MoveRight,
+            TapMode::Lanes => Action::MoveToLane(lanes.lane_at(start_x)),
+        })
+    }
+
+    // The press was interrupted (e.g. the touch was cancelled)
+    pub fn cancel(&mut self) {
+        self.start = None;
+    }
+}
+
+// Position of a pointer event on the canvas, in canvas pixels
+pub fn pointer_position(event: &MouseEvent) -> Option<(f64, f64)> {
+    canvas_position(event.target(), event.client_x() as f64, event.client_y() as f64)
+}
+
+// Position of the touch that changed in a touch event, in canvas pixels
+pub fn touch_position(event: &TouchEvent) -> Option<(f64, f64)> {
+    let touch = event.changed_touches().get(0)?;
+    canvas_position(event.target(), touch.client_x() as f64, touch.client_y() as f64)
+}
+
+// The canvas may be scaled by CSS, so map from its on-screen box to its pixels
+fn canvas_position(target: Option<EventTarget>, client_x: f64, client_y: f64) -> Option<(f64, f64)> {
+    let canvas = target?.dyn_into::<HtmlCanvasElement>().ok()?;
+    let rect = canvas.get_bounding_client_rect();
+    if rect.width() <= 0.0 || rect.height() <= 0.0 {
+        return None;
+    }
+    let x = (client_x - rect.left()) * canvas.width() as f64 / rect.width();
+    let y = (client_y - rect.top()) * canvas.height() as f64 / rect.height();
+    Some((x, y))
+}
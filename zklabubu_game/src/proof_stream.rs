@@ -0,0 +1,102 @@
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{Event, EventSource, MessageEvent};
+use crate::{append_proof_log_line, ProofLogLevel};
+
+// Event names sent by the proving service for a proof job
+const STREAM_EVENTS: [&str; 4] = ["stage", "log", "done", "failed"];
+
+// Browser event callback kept alive for the lifetime of the stream
+type EventListener = Closure<dyn FnMut(Event)>;
+
+// Live proof log subscription (Server-Sent Events)
+pub struct ProofLogStream {
+    source: EventSource,
+    listeners: Vec<(&'static str, EventListener)>,
+    _on_connection_error: EventListener,
+}
+
+impl ProofLogStream {
+    // Subscribe to the event stream of a proof job
+    pub fn connect(url: &str) -> Result<Self, JsValue> {
+        let source = EventSource::new(url)?;
+        let mut listeners = Vec::new();
+
+        for event_name in STREAM_EVENTS {
+            let stream_source = source.clone();
+            let listener = EventListener::new(move |event: Event| {
+                if let Some(message_event) = event.dyn_ref::<MessageEvent>() {
+                    let (message, level) = parse_stream_event(event_name, &message_event.data());
+                    append_proof_log_line(&message, level);
+                }
+
+                // The job is finished, stop the browser from reconnecting
+                if event_name == "done" || event_name == "failed" {
+                    stream_source.close();
+                }
+            });
+            source.add_event_listener_with_callback(event_name, listener.as_ref().unchecked_ref())?;
+            listeners.push((event_name, listener));
+        }
+
+        // Connection lost (service stopped or job unknown)
+        let error_source = source.clone();
+        let on_connection_error = EventListener::new(move |_event: Event| {
+            if error_source.ready_state() == EventSource::CLOSED {
+                append_proof_log_line("Proof log stream disconnected", ProofLogLevel::Error);
+            }
+        });
+        source.set_onerror(Some(on_connection_error.as_ref().unchecked_ref()));
+
+        Ok(ProofLogStream {
+            source,
+            listeners,
+            _on_connection_error: on_connection_error,
+        })
+    }
+
+    // Close the subscription
+    pub fn close(&self) {
+        self.source.close();
+    }
+}
+
+impl Drop for ProofLogStream {
+    fn drop(&mut self) {
+        self.source.close();
+        self.source.set_onerror(None);
+        for (event_name, listener) in &self.listeners {
+            let _ = self.source.remove_event_listener_with_callback(event_name, listener.as_ref().unchecked_ref());
+        }
+    }
+}
+
+// Read `{ "level": ..., "message": ... }` from the event payload
+fn parse_stream_event(event_name: &str, data: &JsValue) -> (String, ProofLogLevel) {
+    let payload = data
+        .as_string()
+        .and_then(|text| js_sys::JSON::parse(&text).ok())
+        .unwrap_or(JsValue::UNDEFINED);
+
+    let field = |name: &str| {
+        js_sys::Reflect::get(&payload, &JsValue::from_str(name))
+            .ok()
+            .and_then(|value| value.as_string())
+    };
+
+    // Fall back to the raw text if the payload is not JSON
+    let message = field("message")
+        .or_else(|| data.as_string())
+        .unwrap_or_default();
+
+    let level = field("level")
+        .and_then(|name| ProofLogLevel::from_name(&name))
+        .unwrap_or_else(|| match event_name {
+            "stage" => ProofLogLevel::Progress,
+            "done" => ProofLogLevel::Success,
+            "failed" => ProofLogLevel::Error,
+            _ => ProofLogLevel::from_message(&message),
+        });
+
+    (message, level)
+}
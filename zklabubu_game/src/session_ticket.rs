@@ -0,0 +1,57 @@
+use wasm_bindgen::prelude::*;
+
+// Session ticket issued by the proving service: the seed of the spawn
+// sequence, an expiry and a nonce, signed by the service. The signature
+// is checked inside the zkVM, the game only needs the seed.
+pub struct SessionTicket {
+    pub seed: u64,
+    pub expires_at: f64, // Unix time (seconds)
+    pub challenge_date: Option<u32>, // Day of a daily challenge (YYYYMMDD)
+    json: String,        // Original ticket, sent back with the proof request
+}
+
+impl SessionTicket {
+    // Parse `{ "seed": "0x..", "expiresAt": 1700000000, "nonce": "0x..", "signature": "0x.." }`
+    pub fn parse(json: &str) -> Result<SessionTicket, JsValue> {
+        let ticket = js_sys::JSON::parse(json)?;
+        let field = |name: &str| js_sys::Reflect::get(&ticket, &JsValue::from_str(name));
+        
+        let seed_hex = field("seed")?
+            .as_string()
+            .ok_or_else(|| JsValue::from_str("Session ticket has no seed"))?;
+        let seed = u64::from_str_radix(seed_hex.trim_start_matches("0x"), 16)
+            .map_err(|_| JsValue::from_str("Session ticket seed is not a 64-bit hex number"))?;
+        
+        let expires_at = field("expiresAt")?
+            .as_f64()
+            .ok_or_else(|| JsValue::from_str("Session ticket has no expiry"))?;
+        
+        for name in ["nonce", "signature"] {
+            if field(name)?.as_string().is_none() {
+                return Err(JsValue::from_str(&format!("Session ticket has no {}", name)));
+            }
+        }
+        
+        // Only daily challenge tickets carry a date
+        let challenge_date = field("challengeDate")?
+            .as_f64()
+            .map(|date| date as u32)
+            .filter(|date| *date != 0);
+        
+        Ok(SessionTicket {
+            seed,
+            expires_at,
+            challenge_date,
+            json: json.to_string(),
+        })
+    }
+    
+    // Has the ticket expired at `now` (Unix time, seconds)?
+    pub fn is_expired(&self, now: f64) -> bool {
+        now >= self.expires_at
+    }
+    
+    pub fn json(&self) -> &str {
+        &self.json
+    }
+}
@@ -19,6 +19,39 @@ pub fn random(min: f64, max: f64) -> f64 {
     min + random_val * (max - min)
 }
 
+// Seeded random number generator (SplitMix64), so a session seed
+// always produces the same spawn sequence
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+    
+    // Seed from the browser's random source
+    pub fn from_entropy() -> Self {
+        let high = random(0.0, 4294967296.0) as u64;
+        let low = random(0.0, 4294967296.0) as u64;
+        Rng::new((high << 32) | low)
+    }
+    
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    
+    // Random number in [min, max)
+    pub fn range(&mut self, min: f64, max: f64) -> f64 {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        min + unit * (max - min)
+    }
+}
+
 // Our own console.log macro
 #[macro_export]
 macro_rules! console_log {
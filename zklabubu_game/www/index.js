@@ -5,11 +5,14 @@
 // Global variables
 let wasmLoaded = false;
 let gameManager = null;
-let animationFrameId;
-let lastTimestamp = 0;
 let soundEnabled = true;
 let proofPanelVisible = false;
 let selectedTeam = null; // 'blue' or 'pink'
+let dailyMode = false; // Playing today's daily challenge
+let dailyTicket = null; // Session ticket of the next daily run
+let gameConfigJson = null; // Game balance (game-config.json), defaults when missing
+const DIFFICULTIES = ['easy', 'normal', 'hard', 'nightmare'];
+let selectedDifficulty = 'normal'; // Difficulty preset, committed by the proof
 
 // DOM elements
 const mainMenuScreen = document.getElementById('main-menu');
@@ -27,6 +30,18 @@ const livesElement = document.getElementById('lives');
 const eggsElement = document.getElementById('game-stars');
 const finalScoreElement = document.getElementById('final-score');
 const finalStatsElement = document.getElementById('final-stats');
+const fpsElement = document.getElementById('fps');
+
+// Frame rate readout, shown with ?fps in the page URL
+const pageParams = new URLSearchParams(window.location.search);
+const showFps = pageParams.has('fps');
+fpsElement.classList.toggle('hidden', !showFps);
+
+// The game draws the HUD on the canvas; ?hud=overlay keeps the HTML counters instead
+const hudMode = pageParams.get('hud') === 'overlay' ? 'overlay' : 'canvas';
+[scoreElement, livesElement, eggsElement].forEach(element => {
+  element.classList.toggle('hidden', hudMode !== 'overlay');
+});
 
 // Buttons
 const globalSoundBtn = document.getElementById('global-sound-btn'); // May be null if DOM not ready
@@ -101,6 +116,8 @@ async function loadWasmModule() {
     window.wasm = wasm;
     console.log("WASM loaded and initialized successfully!");
     wasmLoaded = true;
+    
+    await loadGameConfig();
     updateLoadingProgress(60);
     return true;
   } catch (error) {
@@ -110,17 +127,55 @@ async function loadWasmModule() {
   }
 }
 
+/**
+ * Load the game balance (its hash is committed by the proof)
+ */
+async function loadGameConfig() {
+  try {
+    const response = await fetch('game-config.json');
+    if (response.ok) {
+      gameConfigJson = await response.text();
+      console.log("Game config loaded");
+    } else {
+      console.warn("No game config found, using the default rules");
+    }
+  } catch (error) {
+    console.warn("Could not load game config, using the default rules:", error);
+  }
+}
+
+/**
+ * Capitalize a word (difficulty names)
+ * @param {string} word - Word to capitalize
+ * @returns {string} Capitalized word
+ */
+function capitalize(word) {
+  return word.charAt(0).toUpperCase() + word.slice(1);
+}
+
+/**
+ * Show the selected difficulty on the main menu
+ */
+function updateDifficultyButton() {
+  document.getElementById('difficulty-btn').textContent = `Difficulty: ${capitalize(selectedDifficulty)}`;
+}
+
 /**
  * Toggle pause menu visibility
  * @param {boolean} show - Whether to show or hide
+ * @param {string|null} reason - 'away' when the game paused itself after losing focus
  */
-function togglePauseMenu(show) {
+function togglePauseMenu(show, reason = null) {
+  const pauseReason = document.getElementById('pause-reason');
+  pauseReason.classList.toggle('hidden', !(show && reason === 'away'));
   if (show) {
+    renderKeyBindings();
     pauseMenu.classList.remove('hidden');
     if (gameManager) {
       gameManager.stop();
     }
   } else {
+    capturingKey = null;
     pauseMenu.classList.add('hidden');
     if (gameManager) {
       gameManager.start();
@@ -128,6 +183,88 @@ function togglePauseMenu(show) {
   }
 }
 
+// Rebindable actions and their labels in the Controls table
+const KEY_BINDING_LABELS = {
+  move_left: 'Move Left',
+  move_right: 'Move Right',
+  toggle_pause: 'Pause',
+};
+
+// Key slot waiting for the next key press ({ action, slot }), or null
+let capturingKey = null;
+
+/**
+ * Show a key the way the Controls table lists it
+ * @param {string|null} key - KeyboardEvent.key value
+ * @returns {string} Label for the key
+ */
+function keyLabel(key) {
+  if (!key) return '—';
+  if (key === ' ') return 'Space';
+  if (key.startsWith('Arrow')) return `${key.slice('Arrow'.length)} Arrow`;
+  return key.length === 1 ? key.toUpperCase() : key;
+}
+
+/**
+ * Fill the Controls table of the pause menu from the game's key map
+ */
+function renderKeyBindings() {
+  const table = document.getElementById('key-bindings');
+  if (!table || !gameManager) return;
+
+  const bindings = JSON.parse(gameManager.get_key_bindings());
+  table.innerHTML = '';
+  for (const [action, label] of Object.entries(KEY_BINDING_LABELS)) {
+    const row = table.insertRow();
+    row.insertCell().textContent = label;
+    (bindings[action] || []).forEach((key, slot) => {
+      const button = document.createElement('button');
+      button.className = 'key-button';
+      const capturing = capturingKey && capturingKey.action === action && capturingKey.slot === slot;
+      button.textContent = capturing ? 'Press a key…' : keyLabel(key);
+      button.classList.toggle('capturing', !!capturing);
+      button.addEventListener('click', () => {
+        capturingKey = { action, slot };
+        setKeyBindingMessage('Press a key (Backspace clears, Escape cancels)');
+        renderKeyBindings();
+      });
+      row.insertCell().appendChild(button);
+    });
+  }
+}
+
+/**
+ * Show a hint or error under the Controls table
+ * @param {string} text - Message, or '' to clear it
+ */
+function setKeyBindingMessage(text) {
+  const message = document.getElementById('key-binding-message');
+  if (message) message.textContent = text;
+}
+
+/**
+ * Bind the pressed key to the slot being captured
+ * @param {KeyboardEvent} event - The key press
+ */
+function captureKeyBinding(event) {
+  const { action, slot } = capturingKey;
+  capturingKey = null;
+  try {
+    if (event.key === 'Escape') {
+      setKeyBindingMessage('');
+    } else if (event.key === 'Backspace' || event.key === 'Delete') {
+      gameManager.clear_key_binding(action, slot);
+      setKeyBindingMessage('');
+    } else {
+      gameManager.set_key_binding(action, slot, event.key);
+      setKeyBindingMessage('');
+    }
+  } catch (error) {
+    setKeyBindingMessage(String(error));
+  }
+  renderKeyBindings();
+}
+
 /**
  * Check if all required images are loaded
  * @returns {boolean} Whether all images are loaded
@@ -309,18 +446,10 @@ function startGame() {
   setupCanvas();
   
   // Clear animation loop
-  if (animationFrameId) {
-    cancelAnimationFrame(animationFrameId);
-    animationFrameId = null;
-  }
-  
-  // Reset timestamp
-  lastTimestamp = 0;
+  stopGameLoop();
   
   try {
-    if (gameManager) {
-      gameManager.restart();
-    } else {
+    if (!gameManager) {
       console.log("Creating GameManager with team:", selectedTeam);
       console.log("Available WASM exports:", Object.keys(window.wasm));
       
@@ -340,8 +469,31 @@ function startGame() {
         slowdownImg,
         eggSound,
         rockSound,
-        shieldHitSound
+        shieldHitSound,
+        gameConfigJson
       );
+      
+      // Expose for the SP1 bridge (live proof log)
+      window.gameManager = gameManager;
+      gameManager.set_hud_mode(hudMode);
+    }
+    
+    // The difficulty applies from the restart
+    gameManager.set_difficulty(selectedDifficulty);
+    gameManager.restart();
+    
+    if (dailyMode) {
+      startDailyChallenge();
+    } else {
+      // Seed the spawns from a session ticket when the proving service issued one
+      const ticket = window.SP1Bridge ? window.SP1Bridge.takeSessionTicket() : null;
+      if (ticket) {
+        try {
+          gameManager.start_with_ticket(JSON.stringify(ticket));
+        } catch (error) {
+          console.warn("Could not use session ticket:", error);
+        }
+      }
     }
     
     gameManager.start();
@@ -354,8 +506,8 @@ function startGame() {
       });
     }
     
-    // Start game loop
-    animationFrameId = requestAnimationFrame(gameLoop);
+    // Start game loop (driven by the game, which calls onFrame after each frame)
+    gameManager.run(onFrame);
   } catch (error) {
     console.error("Error starting game:", error);
     console.error("Error details:", error.stack);
@@ -363,6 +515,32 @@ function startGame() {
   }
 }
 
+/**
+ * Seed the game with today's daily challenge (provable when the service issued a ticket)
+ */
+function startDailyChallenge() {
+  const ticket = dailyTicket;
+  dailyTicket = null;
+  if (ticket) {
+    try {
+      gameManager.start_with_ticket(JSON.stringify(ticket));
+      return;
+    } catch (error) {
+      console.warn("Could not use daily challenge ticket:", error);
+    }
+  }
+  
+  // Practice run on the public seed of the day (UTC)
+  gameManager.start_daily(new Date().toISOString().slice(0, 10));
+}
+
+/**
+ * Fetch the ticket of the next daily run
+ */
+async function prepareDailyChallenge() {
+  dailyTicket = window.SP1Bridge ? await window.SP1Bridge.requestDailyTicket() : null;
+}
+
 /**
  * Handle team selection
  */
@@ -370,9 +548,10 @@ function selectTeam(team) {
   console.log("Team selected:", team);
   selectedTeam = team;
   
-  // Stop any existing game first
+  // Stop any existing game (and its frame loop) first
   if (gameManager) {
     try {
+      gameManager.stop_loop();
       gameManager.stop();
     } catch (error) {
       console.warn("Error stopping game manager:", error);
@@ -380,12 +559,6 @@ function selectTeam(team) {
     gameManager = null;
   }
   
-  // Stop animation loop
-  if (animationFrameId) {
-    cancelAnimationFrame(animationFrameId);
-    animationFrameId = null;
-  }
-  
   // Set character images based on team - use already loaded images
   if (team === 'blue') {
     // Use blue character images
@@ -420,10 +593,7 @@ function selectTeam(team) {
  */
 function stopGame() {
   // Stop animation loop first
-  if (animationFrameId) {
-    cancelAnimationFrame(animationFrameId);
-    animationFrameId = null;
-  }
+  stopGameLoop();
   
   // Stop background music
   backgroundMusic.pause();
@@ -440,49 +610,52 @@ function stopGame() {
 }
 
 /**
- * Game loop function
- * @param {number} timestamp - Current time
+ * Stop the game's frame loop (started by gameManager.run)
  */
-function gameLoop(timestamp) {
-  if (!lastTimestamp) lastTimestamp = timestamp;
-  
-  const deltaTime = (timestamp - lastTimestamp) / 1000; // in seconds
-  lastTimestamp = timestamp;
+function stopGameLoop() {
+  if (gameManager) {
+    gameManager.stop_loop();
+  }
+}
+
+/**
+ * Frame hook, called by the game after it updated and drew a frame
+ * @param {boolean} gameOver - Whether the frame ended the game
+ */
+function onFrame(gameOver) {
+  // Gamepads are polled every frame; start opens or closes the pause menu
+  if (gameManager && gameManager.poll_gamepads()) {
+    togglePauseMenu(pauseMenu.classList.contains('hidden'));
+  }
   
-  // If pause menu is open, just refresh the animation frame
+  // If pause menu is open, there is nothing to refresh
   if (!pauseMenu.classList.contains('hidden')) {
-    animationFrameId = requestAnimationFrame(gameLoop);
     return;
   }
   
-  // Update game state
+  // Refresh the page from the game state
   if (gameManager) {
     try {
-      const gameOver = gameManager.update(deltaTime);
-      
-      // Update score and lives
-      const score = gameManager.get_score();
-      const lives = gameManager.get_lives();
-      let visualScore = score;
-  
-      // Use visual_score method if available
-      try {
-        if (typeof gameManager.get_visual_score === 'function') {
-          visualScore = gameManager.get_visual_score();
-        }
-      } catch (e) {
-        console.warn("Could not get visual score:", e);
+      // The tab was hidden or the window lost focus: the game paused itself
+      if (gameManager.take_auto_paused()) {
+        togglePauseMenu(true, 'away');
       }
-  
-      // Get egg counts
-      const yellowEggs = gameManager.get_yellow_eggs_count();
-      const blueEggs = gameManager.get_blue_eggs_count();
-      const purpleEggs = gameManager.get_purple_eggs_count();
       
-      // Update UI
-      scoreElement.textContent = `Score: ${visualScore}`;
-      livesElement.textContent = `Lives: ${lives}`;
-      eggsElement.textContent = `Eggs: 🟡${yellowEggs} 🔵${blueEggs} 🟣${purpleEggs}`;
+      // Update the HTML counters (the canvas HUD draws them otherwise)
+      if (hudMode === 'overlay') {
+        const visualScore = gameManager.get_visual_score();
+        const lives = gameManager.get_lives();
+        const yellowEggs = gameManager.get_yellow_eggs_count();
+        const blueEggs = gameManager.get_blue_eggs_count();
+        const purpleEggs = gameManager.get_purple_eggs_count();
+        
+        scoreElement.textContent = `Score: ${visualScore} · Level ${gameManager.get_level()}`;
+        livesElement.textContent = `Lives: ${lives}`;
+        eggsElement.textContent = `Eggs: 🟡${yellowEggs} 🔵${blueEggs} 🟣${purpleEggs}`;
+      }
+      if (showFps) {
+        fpsElement.textContent = `FPS: ${Math.round(gameManager.get_fps())}`;
+      }
       
       // Check if game is over
       if (gameOver || gameManager.is_game_over() || gameManager.get_game_state() === 3) {
@@ -497,9 +670,6 @@ function gameLoop(timestamp) {
       return;
     }
   }
-  
-  // Schedule next frame
-  animationFrameId = requestAnimationFrame(gameLoop);
 }
 
 /**
@@ -509,10 +679,7 @@ function endGame() {
   console.log("Game ended");
   
   // Stop animation loop
-  if (animationFrameId) {
-    cancelAnimationFrame(animationFrameId);
-    animationFrameId = null;
-  }
+  stopGameLoop();
   
   // Stop background music
   if (backgroundMusic) {
@@ -553,7 +720,20 @@ function endGame() {
       
       // Update results screen
       finalScoreElement.textContent = `Your Score: ${score}`;
-      finalStatsElement.textContent = `Eggs Collected: 🟡${yellowEggs} 🔵${blueEggs} 🟣${purpleEggs}`;
+      finalStatsElement.textContent = `Eggs Collected: 🟡${yellowEggs} 🔵${blueEggs} 🟣${purpleEggs} | ${capitalize(gameManager.get_difficulty())}`;
+      
+      const challengeDate = gameManager.get_challenge_date();
+      if (challengeDate) {
+        finalStatsElement.textContent = `Daily Challenge ${challengeDate} | ${finalStatsElement.textContent}`;
+      }
+      
+      // Achievements unlocked this game (proved from the event ledger)
+      const unlocked = JSON.parse(gameManager.get_achievement_progress())
+        .filter(achievement => achievement.unlocked)
+        .map(achievement => achievement.name);
+      if (unlocked.length > 0) {
+        finalStatsElement.textContent += ` | Achievements: ${unlocked.join(', ')}`;
+      }
       
       // Set background
       gameOverScreen.style.backgroundImage = `url(${bgGameoverImg.src})`;
@@ -573,10 +753,7 @@ function endGame() {
  */
 function goToMainMenu() {
   // Stop animation loop first
-  if (animationFrameId) {
-    cancelAnimationFrame(animationFrameId);
-    animationFrameId = null;
-  }
+  stopGameLoop();
   
   // Stop background music
   if (backgroundMusic) {
@@ -606,10 +783,15 @@ function goToMainMenu() {
 /**
  * Restart the game
  */
-function restartGame() {
+async function restartGame() {
   // Stop current game
   stopGame();
   
+  // Every daily run needs its own ticket
+  if (dailyMode) {
+    await prepareDailyChallenge();
+  }
+  
   // Hide all screens
   document.querySelectorAll('.screen').forEach(screen => {
     screen.style.display = 'none';
@@ -988,6 +1170,9 @@ window.showProofPanel = function() {
         const purpleEggs = gameManager.get_purple_eggs_count();
       const gameTime = gameManager.get_game_time();
       const lives = gameManager.get_lives();
+      const rockHits = gameManager.get_rock_hits();
+      const shieldBlocks = gameManager.get_shield_blocks();
+      const extraLives = gameManager.get_extra_lives();
       
       // Show game data
       console.log("Game Data:", {
@@ -1005,9 +1190,21 @@ window.showProofPanel = function() {
           blueEggs,
           purpleEggs,
           gameTime,
-          lives
+          lives,
+          rockHits,
+          shieldBlocks,
+          extraLives,
+          ledger: JSON.parse(gameManager.get_event_ledger()),
+          config: JSON.parse(gameManager.get_config()),
+          difficulty: gameManager.get_difficulty()
       };
       
+      // Ticket the game was started with (its nonce is the proof's nullifier)
+      const sessionTicket = gameManager.get_session_ticket();
+      if (sessionTicket) {
+          gameData.sessionTicket = JSON.parse(sessionTicket);
+      }
+      
       // Call SP1 Bridge
       window.generateSP1Proof(gameData)
           .then(result => {
@@ -1036,6 +1233,7 @@ document.getElementById('play-blue-btn').addEventListener('click', () => {
   if (buttonSound) {
     buttonSound.play().catch(e => console.warn("Could not play button sound:", e));
   }
+  dailyMode = false;
   selectTeam('blue');
 });
 
@@ -1043,9 +1241,28 @@ document.getElementById('play-pink-btn').addEventListener('click', () => {
   if (buttonSound) {
     buttonSound.play().catch(e => console.warn("Could not play button sound:", e));
   }
+  dailyMode = false;
   selectTeam('pink');
 });
 
+document.getElementById('daily-challenge-btn').addEventListener('click', async () => {
+  if (buttonSound) {
+    buttonSound.play().catch(e => console.warn("Could not play button sound:", e));
+  }
+  dailyMode = true;
+  await prepareDailyChallenge();
+  selectTeam(selectedTeam || 'blue');
+});
+
+document.getElementById('difficulty-btn').addEventListener('click', () => {
+  if (buttonSound) {
+    buttonSound.play().catch(e => console.warn("Could not play button sound:", e));
+  }
+  // Cycle through the presets
+  selectedDifficulty = DIFFICULTIES[(DIFFICULTIES.indexOf(selectedDifficulty) + 1) % DIFFICULTIES.length];
+  updateDifficultyButton();
+});
+
 howToPlayBtn.addEventListener('click', () => showScreen(howToPlayScreen));
 backToMenuBtn.addEventListener('click', () => showScreen(mainMenuScreen));
 homeBtn.addEventListener('click', goToMainMenu);
@@ -1071,9 +1288,8 @@ resumeGameBtn.addEventListener('click', () => {
   if (soundEnabled && buttonSound) buttonSound.play().catch(e => {});
   togglePauseMenu(false);
   
-  if (!animationFrameId) {
-    lastTimestamp = 0;
-    animationFrameId = requestAnimationFrame(gameLoop);
+  if (gameManager && !gameManager.is_running()) {
+    gameManager.run(onFrame);
   }
 });
 restartPauseBtn.addEventListener('click', () => {
@@ -1081,6 +1297,15 @@ restartPauseBtn.addEventListener('click', () => {
   togglePauseMenu(false);
   restartGame();
 });
+document.getElementById('reset-keys-btn').addEventListener('click', () => {
+  if (soundEnabled && buttonSound) buttonSound.play().catch(e => {});
+  if (gameManager) {
+    gameManager.reset_key_bindings();
+  }
+  capturingKey = null;
+  setKeyBindingMessage('Controls reset to the defaults');
+  renderKeyBindings();
+});
 homePauseBtn.addEventListener('click', () => {
   if (soundEnabled && buttonSound) buttonSound.play().catch(e => {});
   togglePauseMenu(false);
@@ -1110,6 +1335,13 @@ window.addEventListener('resize', () => {
 window.addEventListener('keydown', (event) => {
   if (gameScreen.classList.contains('active') && gameManager) {
     try {
+      // A Controls button is waiting for a key: bind it instead of playing
+      if (capturingKey) {
+        event.preventDefault();
+        captureKeyBinding(event);
+        return;
+      }
+      
       // ESC key toggles pause menu
       if (event.key === "Escape") {
         togglePauseMenu(!pauseMenu.classList.contains('hidden'));
@@ -1128,6 +1360,47 @@ window.addEventListener('keydown', (event) => {
   }
 });
 
+// Handle pointer and touch input on the canvas (tap or swipe to move)
+function canvasInputActive() {
+  return gameScreen.classList.contains('active') && gameManager && pauseMenu.classList.contains('hidden');
+}
+
+if (window.PointerEvent) {
+  canvas.addEventListener('pointerdown', (event) => {
+    if (!canvasInputActive()) return;
+    event.preventDefault();
+    gameManager.handle_pointer_down(event);
+  });
+  canvas.addEventListener('pointerup', (event) => {
+    if (!canvasInputActive()) return;
+    event.preventDefault();
+    gameManager.handle_pointer_up(event);
+  });
+  canvas.addEventListener('pointercancel', () => {
+    if (gameManager) gameManager.handle_pointer_cancel();
+  });
+} else {
+  // Older mobile browsers only have touch events
+  canvas.addEventListener('touchstart', (event) => {
+    if (!canvasInputActive()) return;
+    event.preventDefault();
+    gameManager.handle_touch_start(event);
+  }, { passive: false });
+  canvas.addEventListener('touchend', (event) => {
+    if (!canvasInputActive()) return;
+    event.preventDefault();
+    gameManager.handle_touch_end(event);
+  }, { passive: false });
+  canvas.addEventListener('touchcancel', () => {
+    if (gameManager) gameManager.handle_pointer_cancel();
+  });
+}
+
+// Gamepads (polled in the game loop)
+window.addEventListener('gamepadconnected', (event) => {
+  console.log(`Gamepad connected: ${event.gamepad.id}`);
+});
+
 // Game initialization
 document.addEventListener('DOMContentLoaded', () => {
   console.log("DOM loaded, initializing game...");
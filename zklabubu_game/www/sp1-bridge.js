@@ -34,7 +34,7 @@ const SP1Bridge = {
             
             // Try real proof generation
             const result = await this.generateRealProof(gameData);
-            if (result.success) {
+            if (result.success || result.alreadySubmitted) {
                 return result;
             }
             
@@ -62,15 +62,30 @@ const SP1Bridge = {
         }
         
         try {
+            // Follow the live prover log in the SP1 terminal
+            const jobId = await this.createProofJob();
+            if (jobId) {
+                this.streamProofLog(jobId);
+            }
+            
             // API call
             const response = await fetch('http://localhost:3000/api/generate-proof', {
                 method: 'POST',
                 headers: {
                     'Content-Type': 'application/json',
                 },
-                body: JSON.stringify(gameData)
+                body: JSON.stringify(jobId ? { ...gameData, jobId } : gameData)
             });
             
+            // The service already accepted a proof for this game's ticket
+            if (response.status === 409) {
+                const rejection = await response.json();
+                if (typeof window.logToProofPanel === 'function') {
+                    window.logToProofPanel(`Proof rejected: ${rejection.details || rejection.error}`);
+                }
+                return { ...rejection, alreadySubmitted: true };
+            }
+            
             if (!response.ok) {
                 throw new Error(`API error: ${response.status}`);
             }
@@ -146,6 +161,82 @@ const SP1Bridge = {
         }
     },
     
+    // Session ticket fetched ahead of the next game
+    pendingTicket: null,
+    
+    // Ask the proving service for a signed session ticket
+    fetchSessionTicket: async function(options = {}) {
+        if (window.location.hostname.includes('vercel.app') || localStorage.getItem('forceSimulation') === 'true') {
+            return null;
+        }
+        
+        try {
+            const response = await fetch('http://localhost:3000/api/session-tickets', {
+                method: 'POST',
+                headers: { 'Content-Type': 'application/json' },
+                body: JSON.stringify(options)
+            });
+            if (!response.ok) {
+                throw new Error(`API error: ${response.status}`);
+            }
+            return await response.json();
+        } catch (error) {
+            console.warn("Could not get a session ticket:", error);
+            return null;
+        }
+    },
+    
+    // Prefetch the ticket of the next game
+    requestSessionTicket: async function() {
+        this.pendingTicket = null;
+        this.pendingTicket = await this.fetchSessionTicket();
+        return this.pendingTicket;
+    },
+    
+    // Ticket for today's daily challenge (same seed for every player, fresh nonce)
+    requestDailyTicket: function() {
+        return this.fetchSessionTicket({ daily: true });
+    },
+    
+    // Take the prefetched ticket for a new game and fetch the next one
+    takeSessionTicket: function() {
+        const ticket = this.pendingTicket;
+        this.requestSessionTicket();
+        
+        if (!ticket || ticket.expiresAt <= Date.now() / 1000) {
+            return null;
+        }
+        return ticket;
+    },
+    
+    // Create a proof job on the backend (null if the log can't be followed)
+    createProofJob: async function() {
+        try {
+            const response = await fetch('http://localhost:3000/api/proof-jobs', { method: 'POST' });
+            if (!response.ok) {
+                return null;
+            }
+            return (await response.json()).jobId;
+        } catch (error) {
+            console.warn("Could not create proof job:", error);
+            return null;
+        }
+    },
+    
+    // Subscribe the game to the live log of a proof job
+    streamProofLog: function(jobId) {
+        const gameManager = window.gameManager;
+        if (!gameManager || typeof gameManager.stream_proof_log !== 'function') {
+            return;
+        }
+        
+        try {
+            gameManager.stream_proof_log(`http://localhost:3000/api/proof-jobs/${jobId}/events`);
+        } catch (error) {
+            console.warn("Could not stream proof log:", error);
+        }
+    },
+    
     // Simulate proof process
     simulateProofProcess: function(gameData) {
         // Calculated score
@@ -346,6 +437,9 @@ const SP1Bridge = {
 // Global SP1Bridge object
 window.SP1Bridge = SP1Bridge;
 
+// Have a session ticket ready for the first game
+SP1Bridge.requestSessionTicket();
+
 // Global proof generation function
 window.generateSP1Proof = function(gameData) {
     return SP1Bridge.generateProof(gameData);
@@ -27,6 +27,7 @@ module.exports = {
         { from: './index.html', to: './' },
         { from: './style.css', to: './' },
         { from: './sp1-bridge.js', to: './' },
+        { from: './game-config.json', to: './' },
         { from: './assets', to: './assets' },
         // Copy pre-built WASM files if they exist
         ...(pkgExists ? [
@@ -0,0 +1,101 @@
+//! SP1 aggregation program for zkLabubuio tournaments.
+//!
+//! This program verifies many compressed game proofs with SP1 recursion and
+//! commits the top-K leaderboard, so a whole tournament is checked with a
+//! single verification.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use std::collections::BTreeSet;
+
+use alloy_primitives::{Address, B256};
+use alloy_sol_types::SolType;
+use sha2::{Digest, Sha256};
+use zklabubu_proof_lib::{decode_public_values, Difficulty, rank_leaderboard, LeaderboardEntry, LeaderboardStruct, PublicValues};
+
+pub fn main() {
+    // Read input data
+    let game_vkey = sp1_zkvm::io::read::<[u32; 8]>();
+    let top_k = sp1_zkvm::io::read::<u32>();
+    let ticket_issuer = sp1_zkvm::io::read::<Address>();
+    let rules_hash = sp1_zkvm::io::read::<B256>();
+    let allow_untracked = sp1_zkvm::io::read::<bool>();
+    let public_values = sp1_zkvm::io::read::<Vec<Vec<u8>>>();
+    
+    // Verify every game proof and collect its score
+    let mut entries = Vec::with_capacity(public_values.len());
+    let mut digests = BTreeSet::new();
+    let mut nullifiers = BTreeSet::new();
+    let mut challenge_date = None;
+    let mut difficulty = None;
+    for values in &public_values {
+        let digest: [u8; 32] = Sha256::digest(values).into();
+        sp1_zkvm::lib::verify::verify_sp1_proof(&game_vkey, &digest);
+        
+        // The same proof can only be ranked once
+        assert!(digests.insert(digest), "game proof submitted twice");
+        
+        let PublicValues::Full(game) = decode_public_values(values).expect("invalid game public values") else {
+            panic!("only full game proofs can be ranked");
+        };
+        
+        // Games without a ticket have no nullifier, so they are only ranked when explicitly allowed
+        assert!(allow_untracked || !game.nullifier.is_zero(), "game without a nullifier");
+        
+        // A ticketed game can only be ranked once
+        assert!(
+            game.nullifier.is_zero() || nullifiers.insert(game.nullifier),
+            "game submitted twice: nullifier {}",
+            game.nullifier
+        );
+        
+        // Only games ticketed by the pinned service are ranked
+        assert_eq!(game.ticketIssuer, ticket_issuer, "game ticketed by another issuer");
+        
+        // Daily challenges are only ranked against games of the same day
+        let date = *challenge_date.get_or_insert(game.challengeDate);
+        assert_eq!(game.challengeDate, date, "games of different daily challenges");
+        
+        // Games are only ranked under the pinned rules
+        assert_eq!(game.rulesHash, rules_hash, "game played under another game config");
+        
+        // Leaderboards are split per difficulty
+        let preset = *difficulty.get_or_insert(game.difficulty);
+        assert_eq!(game.difficulty, preset, "games of different difficulties");
+        
+        entries.push(LeaderboardEntry {
+            player: game.player,
+            score: game.score,
+            proofDigest: digest.into(),
+        });
+    }
+    
+    let total_games = entries.len() as u32;
+    let entries = rank_leaderboard(entries, top_k as usize);
+    
+    // Debug output
+    println!("Verified {} game proofs", total_games);
+    for (rank, entry) in entries.iter().enumerate() {
+        println!("#{}: score {}", rank + 1, entry.score);
+    }
+    
+    // Game program vkey, as big-endian words
+    let mut vkey_bytes = [0u8; 32];
+    for (chunk, word) in vkey_bytes.chunks_exact_mut(4).zip(game_vkey) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    
+    // Process as output (in a format that can be verified in Solidity)
+    let leaderboard = LeaderboardStruct {
+        gameVkey: vkey_bytes.into(),
+        totalGames: total_games,
+        ticketIssuer: ticket_issuer,
+        challengeDate: challenge_date.unwrap_or_default(),
+        rulesHash: rules_hash,
+        difficulty: difficulty.unwrap_or(Difficulty::Normal.code()),
+        entries,
+    };
+    let bytes = LeaderboardStruct::abi_encode(&leaderboard);
+    sp1_zkvm::io::commit_slice(&bytes);
+}
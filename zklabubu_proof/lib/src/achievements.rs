@@ -0,0 +1,347 @@
+//! Achievements evaluated over the game's event ledger.
+//!
+//! The game records an event for everything that changes the summary. The
+//! program replays the ledger, checks that it adds up to the summary and
+//! commits a bitmask of the unlocked achievements. The game uses the same
+//! definitions to show progress while playing.
+//!
+//! Everything is derived from the ledger alone: the claimed game time is not
+//! proven, so time-based achievements count up to the last ledger event.
+
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::calculate_score;
+
+/// Something that happened during a game.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GameEventKind {
+    YellowEgg,
+    PinkEgg,
+    PurpleEgg,
+    /// A rock hit the player and cost a life
+    RockHit,
+    /// A rock was blocked by the shield
+    ShieldBlock,
+    /// An extra life pickup added a life
+    ExtraLife,
+}
+
+/// One ledger entry.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameEvent {
+    /// Game time of the event (milliseconds)
+    pub at_ms: u32,
+    pub kind: GameEventKind,
+}
+
+/// Statistic an achievement condition looks at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stat {
+    YellowEggs,
+    PinkEggs,
+    PurpleEggs,
+    Eggs,
+    Score,
+    RockHits,
+    ShieldBlocks,
+    ExtraLives,
+    /// Game time of the last ledger event (whole seconds)
+    Seconds,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Condition {
+    /// `stat` reaches `target` during the game
+    AtLeast { stat: Stat, target: u32 },
+    /// `stat` reaches `target` before the first rock hit
+    BeforeFirstHit { stat: Stat, target: u32 },
+}
+
+impl Condition {
+    pub fn target(&self) -> u32 {
+        match self {
+            Condition::AtLeast { target, .. } | Condition::BeforeFirstHit { target, .. } => *target,
+        }
+    }
+}
+
+/// An achievement, committed as bit `bit` of the achievement mask.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Achievement {
+    pub bit: u8,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub condition: Condition,
+}
+
+/// All achievements. Bits are never reused, so old masks keep their meaning.
+pub const ACHIEVEMENTS: &[Achievement] = &[
+    Achievement {
+        bit: 0,
+        name: "Purple Perfection",
+        description: "Collect 10 purple eggs without losing a life",
+        condition: Condition::BeforeFirstHit { stat: Stat::PurpleEggs, target: 10 },
+    },
+    Achievement {
+        bit: 1,
+        name: "Survivor",
+        description: "Survive 120 seconds",
+        condition: Condition::AtLeast { stat: Stat::Seconds, target: 120 },
+    },
+    Achievement {
+        bit: 2,
+        name: "Shield Wall",
+        description: "Block 5 rocks with a shield",
+        condition: Condition::AtLeast { stat: Stat::ShieldBlocks, target: 5 },
+    },
+    Achievement {
+        bit: 3,
+        name: "Egg Hunter",
+        description: "Collect 100 eggs",
+        condition: Condition::AtLeast { stat: Stat::Eggs, target: 100 },
+    },
+    Achievement {
+        bit: 4,
+        name: "High Scorer",
+        description: "Score 1000 points",
+        condition: Condition::AtLeast { stat: Stat::Score, target: 1000 },
+    },
+];
+
+/// Names of the achievements set in `mask`.
+pub fn achievement_names(mask: u32) -> Vec<&'static str> {
+    ACHIEVEMENTS
+        .iter()
+        .filter(|achievement| mask & (1 << achievement.bit) != 0)
+        .map(|achievement| achievement.name)
+        .collect()
+}
+
+/// Counts accumulated from the ledger.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LedgerCounts {
+    pub yellow_eggs: u32,
+    pub pink_eggs: u32,
+    pub purple_eggs: u32,
+    pub rock_hits: u32,
+    pub shield_blocks: u32,
+    pub extra_lives: u32,
+}
+
+impl LedgerCounts {
+    fn stat(&self, stat: Stat, seconds: u32) -> u32 {
+        match stat {
+            Stat::YellowEggs => self.yellow_eggs,
+            Stat::PinkEggs => self.pink_eggs,
+            Stat::PurpleEggs => self.purple_eggs,
+            Stat::Eggs => self.yellow_eggs + self.pink_eggs + self.purple_eggs,
+            // The counts match a plausible summary, whose score fits
+            Stat::Score => calculate_score(self.yellow_eggs, self.pink_eggs, self.purple_eggs).unwrap_or(u32::MAX),
+            Stat::RockHits => self.rock_hits,
+            Stat::ShieldBlocks => self.shield_blocks,
+            Stat::ExtraLives => self.extra_lives,
+            Stat::Seconds => seconds,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerError {
+    /// Events must be recorded in time order
+    OutOfOrder { index: usize },
+    /// The ledger doesn't add up to the game summary
+    Mismatch { ledger: LedgerCounts, summary: LedgerCounts },
+    /// An event happened after the end of the game
+    TooLate { at_ms: u32, game_time: u32 },
+}
+
+impl fmt::Display for LedgerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerError::OutOfOrder { index } => write!(f, "ledger event {} is earlier than the one before", index),
+            LedgerError::Mismatch { ledger, summary } => {
+                write!(f, "ledger adds up to {:?}, the summary reports {:?}", ledger, summary)
+            }
+            LedgerError::TooLate { at_ms, game_time } => {
+                write!(f, "ledger event at {} ms, after the end of a {} second game", at_ms, game_time)
+            }
+        }
+    }
+}
+
+/// Replay of a ledger, fed one event at a time (live in the game, all at once in the program).
+#[derive(Clone, Debug, Default)]
+pub struct LedgerReplay {
+    pub counts: LedgerCounts,
+    /// Counts when the first rock hit the player
+    counts_at_first_hit: Option<LedgerCounts>,
+    pub last_event_ms: u32,
+    events: usize,
+}
+
+impl LedgerReplay {
+    /// Replays a whole ledger.
+    pub fn replay(events: &[GameEvent]) -> Result<Self, LedgerError> {
+        let mut replay = LedgerReplay::default();
+        for event in events {
+            replay.record(event)?;
+        }
+        Ok(replay)
+    }
+
+    pub fn record(&mut self, event: &GameEvent) -> Result<(), LedgerError> {
+        if event.at_ms < self.last_event_ms {
+            return Err(LedgerError::OutOfOrder { index: self.events });
+        }
+        self.last_event_ms = event.at_ms;
+        self.events += 1;
+
+        let counts = &mut self.counts;
+        match event.kind {
+            GameEventKind::YellowEgg => counts.yellow_eggs += 1,
+            GameEventKind::PinkEgg => counts.pink_eggs += 1,
+            GameEventKind::PurpleEgg => counts.purple_eggs += 1,
+            GameEventKind::RockHit => {
+                if self.counts_at_first_hit.is_none() {
+                    self.counts_at_first_hit = Some(*counts);
+                }
+                counts.rock_hits += 1;
+            }
+            GameEventKind::ShieldBlock => counts.shield_blocks += 1,
+            GameEventKind::ExtraLife => counts.extra_lives += 1,
+        }
+        Ok(())
+    }
+
+    /// Checks that the ledger adds up to `summary` and ends within the game time
+    /// (truncated to seconds, so up to one more second was played).
+    pub fn check_summary(&self, summary: &LedgerCounts, game_time: u32) -> Result<(), LedgerError> {
+        if self.counts != *summary {
+            return Err(LedgerError::Mismatch { ledger: self.counts, summary: *summary });
+        }
+        if self.last_event_ms as u64 > (game_time as u64 + 1) * 1000 {
+            return Err(LedgerError::TooLate { at_ms: self.last_event_ms, game_time });
+        }
+        Ok(())
+    }
+
+    /// Progress towards `achievement` (capped at the target).
+    pub fn progress(&self, achievement: &Achievement) -> u32 {
+        let seconds = self.last_event_ms / 1000;
+        let value = match achievement.condition {
+            Condition::AtLeast { stat, .. } => self.counts.stat(stat, seconds),
+            Condition::BeforeFirstHit { stat, .. } => {
+                self.counts_at_first_hit.as_ref().unwrap_or(&self.counts).stat(stat, seconds)
+            }
+        };
+        value.min(achievement.condition.target())
+    }
+
+    /// Bitmask of the unlocked achievements.
+    pub fn unlocked(&self) -> u32 {
+        ACHIEVEMENTS
+            .iter()
+            .filter(|achievement| self.progress(achievement) >= achievement.condition.target())
+            .fold(0, |mask, achievement| mask | (1 << achievement.bit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(at_ms: u32, kind: GameEventKind) -> GameEvent {
+        GameEvent { at_ms, kind }
+    }
+
+    fn achievement(name: &str) -> &'static Achievement {
+        ACHIEVEMENTS.iter().find(|achievement| achievement.name == name).unwrap()
+    }
+
+    #[test]
+    fn record_rejects_out_of_order_events() {
+        let mut replay = LedgerReplay::default();
+        replay.record(&event(1000, GameEventKind::YellowEgg)).unwrap();
+        // Events at the same time are fine
+        replay.record(&event(1000, GameEventKind::PinkEgg)).unwrap();
+        assert_eq!(replay.record(&event(999, GameEventKind::PurpleEgg)), Err(LedgerError::OutOfOrder { index: 2 }));
+
+        let events = [event(500, GameEventKind::YellowEgg), event(200, GameEventKind::RockHit)];
+        assert_eq!(LedgerReplay::replay(&events).unwrap_err(), LedgerError::OutOfOrder { index: 1 });
+    }
+
+    #[test]
+    fn before_first_hit_stops_counting_at_the_first_rock_hit() {
+        let purple_perfection = achievement("Purple Perfection");
+        let mut events: Vec<_> = (0..9).map(|i| event(i * 100, GameEventKind::PurpleEgg)).collect();
+        events.push(event(1000, GameEventKind::RockHit));
+        events.push(event(1100, GameEventKind::PurpleEgg));
+        let replay = LedgerReplay::replay(&events).unwrap();
+        assert_eq!(replay.counts.purple_eggs, 10);
+        assert_eq!(replay.progress(purple_perfection), 9);
+        assert_eq!(replay.unlocked() & (1 << purple_perfection.bit), 0);
+
+        // A shield block is not a hit
+        let mut events: Vec<_> = (0..10).map(|i| event(i * 100, GameEventKind::PurpleEgg)).collect();
+        events.insert(5, event(450, GameEventKind::ShieldBlock));
+        let replay = LedgerReplay::replay(&events).unwrap();
+        assert_eq!(replay.progress(purple_perfection), 10);
+        assert_ne!(replay.unlocked() & (1 << purple_perfection.bit), 0);
+    }
+
+    #[test]
+    fn at_least_counts_the_whole_game() {
+        let shield_wall = achievement("Shield Wall");
+        let events: Vec<_> = (0..7).map(|i| event(i * 100, GameEventKind::ShieldBlock)).collect();
+        let replay = LedgerReplay::replay(&events[..4]).unwrap();
+        assert_eq!(replay.progress(shield_wall), 4);
+        assert_eq!(replay.unlocked(), 0);
+
+        // Progress is capped at the target
+        let replay = LedgerReplay::replay(&events).unwrap();
+        assert_eq!(replay.progress(shield_wall), 5);
+        assert_eq!(replay.unlocked(), 1 << shield_wall.bit);
+
+        let high_scorer = achievement("High Scorer");
+        let events: Vec<_> = (0..50).map(|i| event(i * 100, GameEventKind::PurpleEgg)).collect();
+        let replay = LedgerReplay::replay(&events).unwrap();
+        assert_eq!(replay.progress(high_scorer), 1000);
+        assert_eq!(achievement_names(replay.unlocked()), vec!["Purple Perfection", "High Scorer"]);
+    }
+
+    #[test]
+    fn survivor_counts_up_to_the_last_event() {
+        let survivor = achievement("Survivor");
+        let replay = LedgerReplay::replay(&[event(119_999, GameEventKind::YellowEgg)]).unwrap();
+        assert_eq!(replay.progress(survivor), 119);
+        assert_eq!(replay.unlocked(), 0);
+
+        let replay = LedgerReplay::replay(&[event(120_000, GameEventKind::ShieldBlock)]).unwrap();
+        assert_eq!(replay.progress(survivor), 120);
+        assert_eq!(replay.unlocked(), 1 << survivor.bit);
+        // The last event has to fall within the claimed game time, up to one second later
+        assert_eq!(replay.check_summary(&replay.counts, 119), Ok(()));
+        assert_eq!(
+            replay.check_summary(&replay.counts, 118),
+            Err(LedgerError::TooLate { at_ms: 120_000, game_time: 118 })
+        );
+    }
+
+    #[test]
+    fn check_summary_bounds_the_last_event() {
+        let replay = LedgerReplay::replay(&[event(121_000, GameEventKind::YellowEgg)]).unwrap();
+        let summary = LedgerCounts { yellow_eggs: 1, ..LedgerCounts::default() };
+        // The game time is truncated, so events up to one second later are fine
+        assert_eq!(replay.check_summary(&summary, 120), Ok(()));
+        assert_eq!(
+            replay.check_summary(&summary, 119),
+            Err(LedgerError::TooLate { at_ms: 121_000, game_time: 119 })
+        );
+
+        let summary = LedgerCounts { yellow_eggs: 2, ..LedgerCounts::default() };
+        assert!(matches!(replay.check_summary(&summary, 120), Err(LedgerError::Mismatch { .. })));
+    }
+}
@@ -0,0 +1,355 @@
+//! Game balance, loaded from JSON.
+//!
+//! The game spawns, speeds up and drops power-ups as a [`GameConfig`] says,
+//! and the program checks games against the same config. Its hash is committed
+//! as `rulesHash`, so balance can be tuned without touching code while a
+//! verifier still knows which rules a proof was made under. Values are
+//! integers (percent, milliseconds, pixels per second) so they hash the same
+//! everywhere.
+
+use core::fmt;
+
+use alloy_primitives::{keccak256, B256};
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+
+use crate::rules::{PINK_EGG_POINTS, PURPLE_EGG_POINTS, PURPLE_TOLERANCE_SIGMAS, START_LIVES, YELLOW_EGG_POINTS};
+use crate::{DifficultyRules, GameRules, SpawnChanceRules};
+
+/// Chances of a regular drop (percent, summing to 100).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpawnChances {
+    pub yellow_egg: u32,
+    pub pink_egg: u32,
+    pub purple_egg: u32,
+    pub rock: u32,
+}
+
+impl Default for SpawnChances {
+    fn default() -> Self {
+        SpawnChances {
+            yellow_egg: 45,
+            pink_egg: 20,
+            purple_egg: 10,
+            rock: 25,
+        }
+    }
+}
+
+/// How the game speeds up with the score.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DifficultyCurve {
+    /// Points per difficulty level, up to `fast_levels_until`
+    pub level_score: u32,
+    pub fast_levels_until: u32,
+    /// Points per difficulty level after `fast_levels_until`
+    pub slow_level_score: u32,
+    /// Item speed added per level (percent of the base speed)
+    pub speed_per_level_percent: u32,
+    /// Item speed while a slowdown is active (percent)
+    pub slowdown_speed_percent: u32,
+    pub start_spawn_interval_ms: u32,
+    /// Spawn interval removed per level
+    pub spawn_interval_step_ms: u32,
+    pub min_spawn_interval_ms: u32,
+    /// Most eggs and rocks per spawn, one more per `level_score` points up to this
+    pub max_items_per_spawn: u32,
+}
+
+impl Default for DifficultyCurve {
+    fn default() -> Self {
+        DifficultyCurve {
+            level_score: 100,
+            fast_levels_until: 500,
+            slow_level_score: 500,
+            speed_per_level_percent: 40,
+            slowdown_speed_percent: 60,
+            start_spawn_interval_ms: 1200,
+            spawn_interval_step_ms: 100,
+            min_spawn_interval_ms: 400,
+            max_items_per_spawn: 3,
+        }
+    }
+}
+
+impl DifficultyCurve {
+    /// Difficulty level at `score`.
+    pub fn level(&self, score: u32) -> u32 {
+        if score < self.fast_levels_until {
+            score / self.level_score
+        } else {
+            self.fast_levels_until / self.level_score + (score - self.fast_levels_until) / self.slow_level_score
+        }
+    }
+
+    /// Item speed multiplier at `level`.
+    pub fn speed_multiplier(&self, level: u32, slowdown: bool) -> f64 {
+        let multiplier = 1.0 + level as f64 * self.speed_per_level_percent as f64 / 100.0;
+        if slowdown {
+            multiplier * self.slowdown_speed_percent as f64 / 100.0
+        } else {
+            multiplier
+        }
+    }
+
+    /// Seconds between spawns at `level`.
+    pub fn spawn_interval(&self, level: u32) -> f64 {
+        let interval = self.start_spawn_interval_ms.saturating_sub(level.saturating_mul(self.spawn_interval_step_ms));
+        interval.max(self.min_spawn_interval_ms) as f64 / 1000.0
+    }
+
+    /// Most eggs and rocks in a spawn at `score`.
+    pub fn items_per_spawn(&self, score: u32) -> u32 {
+        (1 + score / self.level_score).min(self.max_items_per_spawn)
+    }
+}
+
+/// Game balance.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GameConfig {
+    /// Lanes items fall in (3 to 9)
+    pub lanes: u32,
+    /// How long Labubu slides from one lane to the next (0 moves at once)
+    pub lane_change_ms: u32,
+    pub spawn_chances: SpawnChances,
+    /// Item speed at difficulty level 0 (pixels per second)
+    pub base_speed: u32,
+    /// How long a shield, 2x points or slowdown lasts
+    pub power_up_duration_ms: u32,
+    pub start_lives: u32,
+    /// Lives can never go above this (extra lives are capped)
+    pub max_lives: u32,
+    /// A power-up drops every time the score passes a multiple of this
+    pub power_up_score_step: u32,
+    /// Slowdown power-ups only drop from this score on
+    pub slowdown_min_score: u32,
+    pub difficulty: DifficultyCurve,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            lanes: 5,
+            lane_change_ms: 120,
+            spawn_chances: SpawnChances::default(),
+            base_speed: 200,
+            power_up_duration_ms: 10_000,
+            start_lives: START_LIVES,
+            max_lives: 5,
+            power_up_score_step: 100,
+            slowdown_min_score: 400,
+            difficulty: DifficultyCurve::default(),
+        }
+    }
+}
+
+/// Why a config can't be played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Json(String),
+    ChancesNot100 { total: u64 },
+    LaneCount { lanes: u32 },
+    /// A score step or interval that must not be zero
+    Zero(&'static str),
+    TooManyItemsPerSpawn { max: u32 },
+    TooFewLives { max_lives: u32, start_lives: u32 },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Json(err) => write!(f, "not valid JSON: {}", err),
+            ConfigError::ChancesNot100 { total } => write!(f, "spawn chances add up to {}%, not 100%", total),
+            ConfigError::LaneCount { lanes } => write!(
+                f,
+                "{} lanes, not between {} and {}",
+                lanes,
+                GameConfig::MIN_LANES,
+                GameConfig::MAX_LANES
+            ),
+            ConfigError::Zero(field) => write!(f, "{} must not be 0", field),
+            ConfigError::TooManyItemsPerSpawn { max } => {
+                write!(f, "at most {} items per spawn fit the lanes next to a power-up", max)
+            }
+            ConfigError::TooFewLives { max_lives, start_lives } => {
+                write!(f, "a cap of {} lives is below the {} starting lives", max_lives, start_lives)
+            }
+        }
+    }
+}
+
+impl GameConfig {
+    pub const MIN_LANES: u32 = 3;
+    pub const MAX_LANES: u32 = 9;
+
+    /// Most eggs and rocks per spawn: one lane stays free for a power-up.
+    pub fn max_items_per_spawn(&self) -> u32 {
+        self.lanes.saturating_sub(1)
+    }
+
+    /// Parses and validates a config.
+    pub fn from_json(json: &str) -> Result<Self, ConfigError> {
+        let config: GameConfig = serde_json::from_str(json).map_err(|err| ConfigError::Json(err.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(Self::MIN_LANES..=Self::MAX_LANES).contains(&self.lanes) {
+            return Err(ConfigError::LaneCount { lanes: self.lanes });
+        }
+
+        let chances = &self.spawn_chances;
+        let total = chances.yellow_egg as u64 + chances.pink_egg as u64 + chances.purple_egg as u64 + chances.rock as u64;
+        if total != 100 {
+            return Err(ConfigError::ChancesNot100 { total });
+        }
+
+        let difficulty = &self.difficulty;
+        for (value, field) in [
+            (difficulty.level_score, "difficulty.levelScore"),
+            (difficulty.slow_level_score, "difficulty.slowLevelScore"),
+            (difficulty.min_spawn_interval_ms, "difficulty.minSpawnIntervalMs"),
+            (difficulty.max_items_per_spawn, "difficulty.maxItemsPerSpawn"),
+            (self.power_up_score_step, "powerUpScoreStep"),
+            (self.start_lives, "startLives"),
+        ] {
+            if value == 0 {
+                return Err(ConfigError::Zero(field));
+            }
+        }
+
+        if difficulty.max_items_per_spawn > self.max_items_per_spawn() {
+            return Err(ConfigError::TooManyItemsPerSpawn { max: self.max_items_per_spawn() });
+        }
+        if self.max_lives < self.start_lives {
+            return Err(ConfigError::TooFewLives { max_lives: self.max_lives, start_lives: self.start_lives });
+        }
+        Ok(())
+    }
+
+    /// The config with the fixed rules, as hashed into `rulesHash`.
+    pub fn rules(&self) -> GameRules {
+        let difficulty = &self.difficulty;
+        GameRules {
+            yellowEggPoints: YELLOW_EGG_POINTS,
+            pinkEggPoints: PINK_EGG_POINTS,
+            purpleEggPoints: PURPLE_EGG_POINTS,
+            purpleToleranceSigmas: PURPLE_TOLERANCE_SIGMAS,
+            lanes: self.lanes,
+            laneChangeMs: self.lane_change_ms,
+            baseSpeed: self.base_speed,
+            powerUpDurationMs: self.power_up_duration_ms,
+            startLives: self.start_lives,
+            maxLives: self.max_lives,
+            powerUpScoreStep: self.power_up_score_step,
+            slowdownMinScore: self.slowdown_min_score,
+            spawnChances: SpawnChanceRules {
+                yellowEgg: self.spawn_chances.yellow_egg,
+                pinkEgg: self.spawn_chances.pink_egg,
+                purpleEgg: self.spawn_chances.purple_egg,
+                rock: self.spawn_chances.rock,
+            },
+            difficulty: DifficultyRules {
+                levelScore: difficulty.level_score,
+                fastLevelsUntil: difficulty.fast_levels_until,
+                slowLevelScore: difficulty.slow_level_score,
+                speedPerLevelPercent: difficulty.speed_per_level_percent,
+                slowdownSpeedPercent: difficulty.slowdown_speed_percent,
+                startSpawnIntervalMs: difficulty.start_spawn_interval_ms,
+                spawnIntervalStepMs: difficulty.spawn_interval_step_ms,
+                minSpawnIntervalMs: difficulty.min_spawn_interval_ms,
+                maxItemsPerSpawn: difficulty.max_items_per_spawn,
+            },
+        }
+    }
+
+    /// `keccak256(abi.encode(rules))`, committed as `rulesHash`.
+    pub fn rules_hash(&self) -> B256 {
+        keccak256(GameRules::abi_encode(&self.rules()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_slows_down_after_the_fast_levels() {
+        let curve = DifficultyCurve::default();
+        assert_eq!(curve.level(0), 0);
+        assert_eq!(curve.level(99), 0);
+        assert_eq!(curve.level(100), 1);
+        assert_eq!(curve.level(499), 4);
+        // From 500 points on a level takes 500 points
+        assert_eq!(curve.level(500), 5);
+        assert_eq!(curve.level(999), 5);
+        assert_eq!(curve.level(1000), 6);
+        assert_eq!(curve.level(u32::MAX), 5 + (u32::MAX - 500) / 500);
+    }
+
+    #[test]
+    fn spawn_interval_stops_at_the_minimum() {
+        let curve = DifficultyCurve::default();
+        assert_eq!(curve.spawn_interval(0), 1.2);
+        assert_eq!(curve.spawn_interval(3), 0.9);
+        assert_eq!(curve.spawn_interval(8), 0.4);
+        assert_eq!(curve.spawn_interval(100), 0.4);
+        assert_eq!(curve.spawn_interval(u32::MAX), 0.4);
+    }
+
+    #[test]
+    fn items_per_spawn_is_capped() {
+        let curve = DifficultyCurve::default();
+        assert_eq!(curve.items_per_spawn(0), 1);
+        assert_eq!(curve.items_per_spawn(150), 2);
+        assert_eq!(curve.items_per_spawn(10_000), 3);
+    }
+
+    #[test]
+    fn validate_accepts_the_default() {
+        assert_eq!(GameConfig::default().validate(), Ok(()));
+        assert_eq!(GameConfig::from_json("{}"), Ok(GameConfig::default()));
+    }
+
+    #[test]
+    fn validate_rejects_unplayable_configs() {
+        let config = GameConfig { lanes: 2, ..GameConfig::default() };
+        assert_eq!(config.validate(), Err(ConfigError::LaneCount { lanes: 2 }));
+
+        let mut config = GameConfig::default();
+        config.spawn_chances.rock = u32::MAX;
+        assert_eq!(config.validate(), Err(ConfigError::ChancesNot100 { total: 75 + u32::MAX as u64 }));
+
+        let mut config = GameConfig::default();
+        config.difficulty.level_score = 0;
+        assert_eq!(config.validate(), Err(ConfigError::Zero("difficulty.levelScore")));
+
+        let mut config = GameConfig::default();
+        config.difficulty.max_items_per_spawn = 5;
+        assert_eq!(config.validate(), Err(ConfigError::TooManyItemsPerSpawn { max: 4 }));
+
+        let config = GameConfig { max_lives: 2, ..GameConfig::default() };
+        assert_eq!(config.validate(), Err(ConfigError::TooFewLives { max_lives: 2, start_lives: 3 }));
+    }
+
+    #[test]
+    fn from_json_rejects_invalid_json() {
+        assert!(matches!(GameConfig::from_json("{"), Err(ConfigError::Json(_))));
+        assert!(matches!(GameConfig::from_json(r#"{"baseSpeed": -1}"#), Err(ConfigError::Json(_))));
+        let json = r#"{"spawnChances": {"rock": 30}}"#;
+        assert_eq!(GameConfig::from_json(json), Err(ConfigError::ChancesNot100 { total: 105 }));
+        assert_eq!(GameConfig::from_json(r#"{"lanes": 10}"#), Err(ConfigError::LaneCount { lanes: 10 }));
+    }
+
+    #[test]
+    fn rules_hash_covers_the_config() {
+        let config = GameConfig::default();
+        assert_eq!(config.rules_hash(), config.clone().rules_hash());
+        let faster = GameConfig { base_speed: 201, ..GameConfig::default() };
+        assert_ne!(faster.rules_hash(), config.rules_hash());
+    }
+}
@@ -0,0 +1,114 @@
+//! Daily challenges: every player faces the same spawn sequence on a given UTC day.
+//!
+//! The seed of the day is `keccak256(DAILY_DOMAIN || "YYYY-MM-DD" || secret)`.
+//! Without a secret anyone can compute it. With one, only the game service can,
+//! and it hands the seed out in a session ticket carrying the challenge date,
+//! so tomorrow's sequence can't be practised today. The program commits the
+//! ticket's date as `challengeDate` (`YYYYMMDD`, 0 for a regular game). The
+//! date is only as trustworthy as the ticket issuer, which verifiers pin.
+
+use alloy_primitives::keccak256;
+
+/// Domain of the daily seed.
+pub const DAILY_DOMAIN: &[u8] = b"zklabubu-sp1.daily";
+
+/// Seed of the daily challenge of `date` (`YYYY-MM-DD`).
+pub fn daily_seed(date: &str, secret: &[u8]) -> u64 {
+    let mut preimage = Vec::with_capacity(DAILY_DOMAIN.len() + date.len() + secret.len());
+    preimage.extend_from_slice(DAILY_DOMAIN);
+    preimage.extend_from_slice(date.as_bytes());
+    preimage.extend_from_slice(secret);
+    let hash = keccak256(preimage);
+    u64::from_be_bytes(hash[..8].try_into().unwrap())
+}
+
+/// Parses a `YYYY-MM-DD` date into its committed form `YYYYMMDD`.
+pub fn parse_challenge_date(date: &str) -> Option<u32> {
+    let bytes = date.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let number = |range: core::ops::Range<usize>| -> Option<u32> {
+        let digits = date.get(range)?;
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        digits.parse().ok()
+    };
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    Some(year * 10000 + month * 100 + day)
+}
+
+/// Whether a committed `YYYYMMDD` date is a real day.
+pub fn is_valid_challenge_date(date: u32) -> bool {
+    parse_challenge_date(&format_challenge_date(date)) == Some(date)
+}
+
+/// Formats a committed `YYYYMMDD` date as `YYYY-MM-DD`.
+pub fn format_challenge_date(date: u32) -> String {
+    format!("{:04}-{:02}-{:02}", date / 10000, date / 100 % 100, date % 100)
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    // Leap years: divisible by 4, centuries only when divisible by 400
+    let leap = matches!((year % 4, year % 100, year % 400), (0, 1.., _) | (_, _, 0));
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_challenge_date_follows_leap_years() {
+        assert_eq!(parse_challenge_date("2024-02-29"), Some(20240229));
+        assert_eq!(parse_challenge_date("2000-02-29"), Some(20000229));
+        assert_eq!(parse_challenge_date("2023-02-29"), None);
+        // Centuries are only leap years when divisible by 400
+        assert_eq!(parse_challenge_date("1900-02-29"), None);
+        assert_eq!(parse_challenge_date("2100-02-29"), None);
+    }
+
+    #[test]
+    fn parse_challenge_date_rejects_malformed_dates() {
+        assert_eq!(parse_challenge_date("2025-04-30"), Some(20250430));
+        assert_eq!(parse_challenge_date("2025-12-31"), Some(20251231));
+        for date in [
+            "2025-04-31", "2025-13-01", "2025-00-10", "2025-01-00", "2025-1-01",
+            "2025/01/01", "+025-01-01", "2025-01-0a", "",
+        ] {
+            assert_eq!(parse_challenge_date(date), None, "{}", date);
+        }
+    }
+
+    #[test]
+    fn format_challenge_date_round_trips() {
+        assert_eq!(format_challenge_date(20250105), "2025-01-05");
+        assert_eq!(parse_challenge_date(&format_challenge_date(20240229)), Some(20240229));
+    }
+
+    #[test]
+    fn is_valid_challenge_date_checks_the_committed_form() {
+        assert!(is_valid_challenge_date(20240229));
+        assert!(!is_valid_challenge_date(20230229));
+        assert!(!is_valid_challenge_date(20251301));
+        assert!(!is_valid_challenge_date(0));
+        // More than four digits of year don't format back to the same date
+        assert!(!is_valid_challenge_date(u32::MAX));
+    }
+
+    #[test]
+    fn daily_seed_depends_on_the_date_and_secret() {
+        assert_eq!(daily_seed("2025-01-05", b""), daily_seed("2025-01-05", b""));
+        assert_ne!(daily_seed("2025-01-05", b""), daily_seed("2025-01-06", b""));
+        assert_ne!(daily_seed("2025-01-05", b""), daily_seed("2025-01-05", b"secret"));
+    }
+}
@@ -0,0 +1,165 @@
+//! Difficulty presets.
+//!
+//! A preset adjusts the [`GameConfig`] the game is played with: the base item
+//! speed, the slope of the speed ramp, the share of rocks and the starting
+//! lives. The program applies the preset itself and commits it as
+//! `difficulty`, so leaderboards can be split per difficulty while
+//! `rulesHash` still covers the adjusted config.
+
+use serde::{Deserialize, Serialize};
+
+use crate::GameConfig;
+
+/// Read by the program after the game config.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    /// The config as it is
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+/// How a preset changes the config.
+struct Preset {
+    /// Base item speed (percent of the config's)
+    base_speed_percent: u32,
+    /// Item speed added per level (percent of the base speed)
+    speed_per_level_percent: u32,
+    /// Rock chance moved from (negative) or to yellow eggs (percent points)
+    rock_chance_delta: i32,
+    start_lives: u32,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Nightmare];
+
+    /// `difficulty` word of the versioned public values.
+    pub fn code(self) -> u32 {
+        match self {
+            Difficulty::Easy => 0,
+            Difficulty::Normal => 1,
+            Difficulty::Hard => 2,
+            Difficulty::Nightmare => 3,
+        }
+    }
+
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|difficulty| difficulty.code() == code)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Nightmare => "nightmare",
+        }
+    }
+
+    /// Parses a preset name (`easy`, `normal`, `hard` or `nightmare`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|difficulty| difficulty.name().eq_ignore_ascii_case(name))
+    }
+
+    fn preset(self) -> Option<Preset> {
+        match self {
+            Difficulty::Easy => Some(Preset {
+                base_speed_percent: 80,
+                speed_per_level_percent: 25,
+                rock_chance_delta: -10,
+                start_lives: 5,
+            }),
+            Difficulty::Normal => None,
+            Difficulty::Hard => Some(Preset {
+                base_speed_percent: 120,
+                speed_per_level_percent: 55,
+                rock_chance_delta: 10,
+                start_lives: 3,
+            }),
+            Difficulty::Nightmare => Some(Preset {
+                base_speed_percent: 150,
+                speed_per_level_percent: 70,
+                rock_chance_delta: 20,
+                start_lives: 1,
+            }),
+        }
+    }
+
+    /// The config adjusted by this preset (validate the result before playing it).
+    pub fn apply(self, config: &GameConfig) -> GameConfig {
+        let mut config = config.clone();
+        let Some(preset) = self.preset() else {
+            return config;
+        };
+
+        // Saturates instead of overflowing on an absurd base speed
+        let base_speed = config.base_speed as u64 * preset.base_speed_percent as u64 / 100;
+        config.base_speed = u32::try_from(base_speed).unwrap_or(u32::MAX);
+        config.difficulty.speed_per_level_percent = preset.speed_per_level_percent;
+        // Rocks take the place of yellow eggs; a config without enough of either fails validation
+        let chances = &mut config.spawn_chances;
+        chances.rock = chances.rock.saturating_add_signed(preset.rock_chance_delta);
+        chances.yellow_egg = chances.yellow_egg.saturating_add_signed(-preset.rock_chance_delta);
+        config.start_lives = preset.start_lives;
+        config.max_lives = config.max_lives.max(preset.start_lives);
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConfigError, SpawnChances};
+
+    #[test]
+    fn normal_keeps_the_config() {
+        let config = GameConfig::default();
+        assert_eq!(Difficulty::Normal.apply(&config), config);
+    }
+
+    #[test]
+    fn presets_of_the_default_config_are_playable() {
+        for difficulty in Difficulty::ALL {
+            assert_eq!(difficulty.apply(&GameConfig::default()).validate(), Ok(()), "{}", difficulty.name());
+        }
+
+        let hard = Difficulty::Hard.apply(&GameConfig::default());
+        assert_eq!(hard.base_speed, 240);
+        assert_eq!(hard.difficulty.speed_per_level_percent, 55);
+        assert_eq!((hard.spawn_chances.rock, hard.spawn_chances.yellow_egg), (35, 35));
+        assert_eq!(hard.start_lives, 3);
+    }
+
+    #[test]
+    fn apply_saturates_into_an_invalid_config() {
+        let config = GameConfig {
+            spawn_chances: SpawnChances { yellow_egg: 65, pink_egg: 20, purple_egg: 10, rock: 5 },
+            ..GameConfig::default()
+        };
+        let easy = Difficulty::Easy.apply(&config);
+        // The rock chance stops at 0 while yellow eggs still gain the full 10 points
+        assert_eq!((easy.spawn_chances.rock, easy.spawn_chances.yellow_egg), (0, 75));
+        assert_eq!(easy.validate(), Err(ConfigError::ChancesNot100 { total: 105 }));
+    }
+
+    #[test]
+    fn apply_does_not_overflow_the_base_speed() {
+        let config = GameConfig { base_speed: u32::MAX, ..GameConfig::default() };
+        assert_eq!(Difficulty::Nightmare.apply(&config).base_speed, u32::MAX);
+        assert_eq!(Difficulty::Easy.apply(&config).base_speed, (u32::MAX as u64 * 80 / 100) as u32);
+    }
+
+    #[test]
+    fn codes_and_names_round_trip() {
+        for difficulty in Difficulty::ALL {
+            assert_eq!(Difficulty::from_code(difficulty.code()), Some(difficulty));
+            assert_eq!(Difficulty::from_name(difficulty.name()), Some(difficulty));
+        }
+        assert_eq!(Difficulty::from_name("HARD"), Some(Difficulty::Hard));
+        assert_eq!(Difficulty::from_code(4), None);
+        assert_eq!(Difficulty::from_name("insane"), None);
+    }
+}
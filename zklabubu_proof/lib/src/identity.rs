@@ -0,0 +1,69 @@
+//! Player identity: the player signs the game session to claim the proof.
+
+use alloy_primitives::{keccak256, Address, Signature, SignatureError, B256};
+use alloy_sol_types::SolType;
+
+use crate::GameSession;
+
+/// Digest of a game session, signed by the player with `personal_sign` (EIP-191).
+pub fn session_digest(session: &GameSession) -> B256 {
+    keccak256(GameSession::abi_encode(session))
+}
+
+/// Recovers the address that signed the session digest.
+///
+/// `signature` is the 65-byte `r || s || v` signature returned by the wallet.
+pub fn recover_player(session: &GameSession, signature: &[u8]) -> Result<Address, SignatureError> {
+    let signature = Signature::try_from(signature)?;
+    signature.recover_address_from_msg(session_digest(session))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::eip191_hash_message;
+    use k256::ecdsa::SigningKey;
+
+    fn session() -> GameSession {
+        GameSession {
+            score: 120,
+            yellowEggs: 10,
+            pinkEggs: 3,
+            purpleEggs: 2,
+            gameTime: 60,
+            lives: 1,
+            rockHits: 2,
+            shieldBlocks: 0,
+            extraLives: 0,
+            nonce: B256::repeat_byte(1),
+        }
+    }
+
+    // Signs like `personal_sign` over the session digest
+    fn sign(key: &SigningKey, session: &GameSession) -> [u8; 65] {
+        let hash = eip191_hash_message(session_digest(session));
+        let (signature, recovery_id) = key.sign_prehash_recoverable(hash.as_slice()).unwrap();
+        Signature::from((signature, recovery_id)).as_bytes()
+    }
+
+    #[test]
+    fn recovers_the_signing_player() {
+        let key = SigningKey::from_slice(&[7; 32]).unwrap();
+        let signature = sign(&key, &session());
+        assert_eq!(recover_player(&session(), &signature).unwrap(), Address::from_private_key(&key));
+    }
+
+    #[test]
+    fn tampered_session_recovers_another_address() {
+        let key = SigningKey::from_slice(&[7; 32]).unwrap();
+        let signature = sign(&key, &session());
+        let tampered = GameSession { score: 1200, ..session() };
+        let recovered = recover_player(&tampered, &signature).unwrap();
+        assert_ne!(recovered, Address::from_private_key(&key));
+    }
+
+    #[test]
+    fn rejects_a_malformed_signature() {
+        assert!(recover_player(&session(), &[0; 64]).is_err());
+    }
+}
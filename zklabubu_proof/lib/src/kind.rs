@@ -0,0 +1,139 @@
+//! Proof kinds: what the program commits about a game.
+//!
+//! A full proof commits the whole summary. A private proof replaces the egg
+//! breakdown with a salted commitment. A threshold proof only commits whether
+//! the score reached a public threshold, with a hiding commitment to the exact
+//! score and egg breakdown that the player can open later.
+
+use alloy_primitives::{keccak256, B256};
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+
+use crate::{calculate_score, ScoreOpening, ThresholdPublicValuesStruct};
+
+/// Read by the program after the game data.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProofKind {
+    /// Commit `PublicValuesV2`: the whole game summary
+    #[default]
+    Full,
+    /// Commit `PrivatePublicValuesStruct`: the egg counts are hidden
+    Private {
+        /// Random salt of the egg commitment, kept by the player
+        salt: B256,
+    },
+    /// Commit `ThresholdPublicValuesStruct`: only whether `score >= threshold`
+    Threshold {
+        threshold: u32,
+        /// Random salt hiding the score in the commitment, kept by the player
+        salt: B256,
+    },
+}
+
+/// `kind` word of the versioned public values.
+pub const KIND_FULL: u32 = 0;
+pub const KIND_PRIVATE: u32 = 1;
+pub const KIND_THRESHOLD: u32 = 2;
+
+impl ProofKind {
+    pub fn code(&self) -> u32 {
+        match self {
+            ProofKind::Full => KIND_FULL,
+            ProofKind::Private { .. } => KIND_PRIVATE,
+            ProofKind::Threshold { .. } => KIND_THRESHOLD,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProofKind::Full => "full",
+            ProofKind::Private { .. } => "private",
+            ProofKind::Threshold { .. } => "threshold",
+        }
+    }
+}
+
+/// Hiding commitment to a score and its egg breakdown: `keccak256(abi.encode(opening))`.
+pub fn score_commitment(opening: &ScoreOpening) -> B256 {
+    keccak256(ScoreOpening::abi_encode(opening))
+}
+
+/// Whether a score reaches the threshold, committed as `passed`.
+pub fn passes_threshold(score: u32, threshold: u32) -> bool {
+    score >= threshold
+}
+
+/// Checks an opening against the public values of a threshold proof: it must open
+/// the commitment, its eggs must add up to its score, and that score must pass or
+/// fail the threshold as committed.
+pub fn verify_score_opening(opening: &ScoreOpening, public_values: &ThresholdPublicValuesStruct) -> bool {
+    score_commitment(opening) == public_values.scoreCommitment
+        && calculate_score(opening.yellowEggs, opening.pinkEggs, opening.purpleEggs) == Some(opening.score)
+        && passes_threshold(opening.score, public_values.threshold) == public_values.passed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opening() -> ScoreOpening {
+        ScoreOpening { salt: B256::repeat_byte(1), score: 120, yellowEggs: 10, pinkEggs: 3, purpleEggs: 2 }
+    }
+
+    #[test]
+    fn score_commitment_hides_behind_the_salt() {
+        assert_eq!(score_commitment(&opening()), score_commitment(&opening()));
+        let resalted = ScoreOpening { salt: B256::repeat_byte(2), ..opening() };
+        assert_ne!(score_commitment(&resalted), score_commitment(&opening()));
+    }
+
+    #[test]
+    fn score_commitment_binds_the_score_and_eggs() {
+        let higher = ScoreOpening { score: 121, ..opening() };
+        assert_ne!(score_commitment(&higher), score_commitment(&opening()));
+        let other_eggs = ScoreOpening { yellowEggs: 12, pinkEggs: 2, ..opening() };
+        assert_ne!(score_commitment(&other_eggs), score_commitment(&opening()));
+    }
+
+    fn threshold_values(threshold: u32, passed: bool) -> ThresholdPublicValuesStruct {
+        ThresholdPublicValuesStruct {
+            version: crate::PUBLIC_VALUES_VERSION,
+            kind: KIND_THRESHOLD,
+            gameId: crate::game_id(),
+            rulesHash: B256::ZERO,
+            difficulty: 1,
+            threshold,
+            passed,
+            scoreCommitment: score_commitment(&opening()),
+            player: Default::default(),
+            nullifier: B256::ZERO,
+            ticketIssuer: Default::default(),
+            challengeDate: 0,
+        }
+    }
+
+    #[test]
+    fn passes_threshold_rejects_a_lower_score() {
+        assert!(passes_threshold(120, 120));
+        assert!(passes_threshold(121, 120));
+        assert!(!passes_threshold(119, 120));
+        assert!(passes_threshold(0, 0));
+    }
+
+    #[test]
+    fn verify_score_opening_checks_the_threshold_result() {
+        assert!(verify_score_opening(&opening(), &threshold_values(100, true)));
+        assert!(verify_score_opening(&opening(), &threshold_values(121, false)));
+        // A score below the threshold can't back a passed proof, nor one above it a failed proof
+        assert!(!verify_score_opening(&opening(), &threshold_values(121, true)));
+        assert!(!verify_score_opening(&opening(), &threshold_values(100, false)));
+        // The opening must match the commitment, and its eggs the score
+        let other_salt = ScoreOpening { salt: B256::repeat_byte(2), ..opening() };
+        assert!(!verify_score_opening(&other_salt, &threshold_values(100, true)));
+        let mut inflated = threshold_values(100, true);
+        let opening = ScoreOpening { score: 500, ..opening() };
+        inflated.scoreCommitment = score_commitment(&opening);
+        assert!(!verify_score_opening(&opening, &inflated));
+    }
+}
@@ -1,7 +1,33 @@
 use alloy_sol_types::sol;
 
+mod achievements;
+mod config;
+mod daily;
+mod difficulty;
+mod identity;
+mod kind;
+mod public_values;
+pub mod rules;
+mod reveal;
+mod ticket;
+
+pub use achievements::{
+    achievement_names, Achievement, Condition, GameEvent, GameEventKind, LedgerCounts, LedgerError, LedgerReplay, Stat,
+    ACHIEVEMENTS,
+};
+pub use config::{ConfigError, DifficultyCurve, GameConfig, SpawnChances};
+pub use difficulty::Difficulty;
+pub use daily::{daily_seed, format_challenge_date, is_valid_challenge_date, parse_challenge_date, DAILY_DOMAIN};
+pub use identity::{recover_player, session_digest};
+pub use kind::{passes_threshold, score_commitment, verify_score_opening, ProofKind};
+pub use public_values::{decode_public_values, game_id, PublicValues, PublicValuesError, PUBLIC_VALUES_VERSION};
+pub use reveal::{egg_commitment, EggReveal};
+pub use rules::{check_lives, check_plausibility, Implausible};
+pub use ticket::{ticket_digest, SignedTicket, TicketError};
+
 sol! {
-    /// Structure containing game results that can be easily deserialized by Solidity.
+    /// Version 1 public values: the original six fields, no version field.
+    /// Kept to decode older proofs.
     struct PublicValuesStruct {
         uint32 score;
         uint32 yellowEggs;
@@ -10,14 +36,182 @@ sol! {
         uint32 gameTime;
         uint32 lives;
     }
+
+    /// Structure containing game results that can be easily deserialized by Solidity.
+    /// Every versioned struct starts with `version`, `kind`, `gameId`, `rulesHash` and `difficulty`.
+    struct PublicValuesV2 {
+        uint32 version;
+        uint32 kind;
+        bytes32 gameId;
+        bytes32 rulesHash;
+        uint32 difficulty;
+        uint32 score;
+        uint32 yellowEggs;
+        uint32 pinkEggs;
+        uint32 purpleEggs;
+        uint32 gameTime;
+        uint32 lives;
+        uint32 rockHits;
+        uint32 shieldBlocks;
+        uint32 extraLives;
+        uint32 achievements;
+        address player;
+        bytes32 nullifier;
+        uint64 seed;
+        uint64 ticketExpiresAt;
+        address ticketIssuer;
+        uint32 challengeDate;
+    }
+
+    /// Public values of a private proof: the egg breakdown is replaced by a salted commitment.
+    struct PrivatePublicValuesStruct {
+        uint32 version;
+        uint32 kind;
+        bytes32 gameId;
+        bytes32 rulesHash;
+        uint32 difficulty;
+        uint32 score;
+        bytes32 eggsCommitment;
+        uint32 gameTime;
+        uint32 lives;
+        uint32 rockHits;
+        uint32 shieldBlocks;
+        uint32 extraLives;
+        uint32 achievements;
+        address player;
+        bytes32 nullifier;
+        uint64 seed;
+        uint64 ticketExpiresAt;
+        address ticketIssuer;
+        uint32 challengeDate;
+    }
+
+    /// Public values of a threshold proof: the exact score stays private.
+    struct ThresholdPublicValuesStruct {
+        uint32 version;
+        uint32 kind;
+        bytes32 gameId;
+        bytes32 rulesHash;
+        uint32 difficulty;
+        uint32 threshold;
+        bool passed;
+        bytes32 scoreCommitment;
+        address player;
+        bytes32 nullifier;
+        address ticketIssuer;
+        uint32 challengeDate;
+    }
+
+    /// Opening of a threshold proof's score commitment.
+    struct ScoreOpening {
+        bytes32 salt;
+        uint32 score;
+        uint32 yellowEggs;
+        uint32 pinkEggs;
+        uint32 purpleEggs;
+    }
+
+    /// Spawn chances of the game rules (`SpawnChances`).
+    struct SpawnChanceRules {
+        uint32 yellowEgg;
+        uint32 pinkEgg;
+        uint32 purpleEgg;
+        uint32 rock;
+    }
+
+    /// Difficulty curve of the game rules (`DifficultyCurve`).
+    struct DifficultyRules {
+        uint32 levelScore;
+        uint32 fastLevelsUntil;
+        uint32 slowLevelScore;
+        uint32 speedPerLevelPercent;
+        uint32 slowdownSpeedPercent;
+        uint32 startSpawnIntervalMs;
+        uint32 spawnIntervalStepMs;
+        uint32 minSpawnIntervalMs;
+        uint32 maxItemsPerSpawn;
+    }
+
+    /// Game rules (the `GameConfig` and the fixed rules), hashed into `rulesHash`.
+    struct GameRules {
+        uint32 yellowEggPoints;
+        uint32 pinkEggPoints;
+        uint32 purpleEggPoints;
+        uint32 purpleToleranceSigmas;
+        uint32 lanes;
+        uint32 laneChangeMs;
+        uint32 baseSpeed;
+        uint32 powerUpDurationMs;
+        uint32 startLives;
+        uint32 maxLives;
+        uint32 powerUpScoreStep;
+        uint32 slowdownMinScore;
+        SpawnChanceRules spawnChances;
+        DifficultyRules difficulty;
+    }
+
+    /// Game session signed by the player to bind a proof to their address.
+    struct GameSession {
+        uint32 score;
+        uint32 yellowEggs;
+        uint32 pinkEggs;
+        uint32 purpleEggs;
+        uint32 gameTime;
+        uint32 lives;
+        uint32 rockHits;
+        uint32 shieldBlocks;
+        uint32 extraLives;
+        bytes32 nonce;
+    }
+
+    /// Session ticket issued by the game service for one game.
+    /// `challengeDate` is the day of a daily challenge (`YYYYMMDD`), 0 for a regular game.
+    struct SessionTicket {
+        uint64 seed;
+        uint64 expiresAt;
+        bytes32 nonce;
+        uint32 challengeDate;
+    }
+
+    /// One ranked game of an aggregated tournament.
+    struct LeaderboardEntry {
+        address player;
+        uint32 score;
+        bytes32 proofDigest;
+    }
+
+    /// Public values of the aggregation program: the top games of a tournament.
+    /// All games of a daily leaderboard share its `challengeDate` (0 for regular games),
+    /// and all games share the `rulesHash` and `difficulty` they were played under and the
+    /// `ticketIssuer` of their session tickets (zero for games without a ticket).
+    struct LeaderboardStruct {
+        bytes32 gameVkey;
+        uint32 totalGames;
+        address ticketIssuer;
+        uint32 challengeDate;
+        bytes32 rulesHash;
+        uint32 difficulty;
+        LeaderboardEntry[] entries;
+    }
 }
 
-/// Function to calculate score based on egg types
-pub fn calculate_score(yellow_eggs: u32, blue_eggs: u32, purple_eggs: u32) -> u32 {
-    (yellow_eggs * 5) + (blue_eggs * 10) + (purple_eggs * 20)
+/// Function to calculate score based on egg types (`None` if it overflows)
+pub fn calculate_score(yellow_eggs: u32, blue_eggs: u32, purple_eggs: u32) -> Option<u32> {
+    yellow_eggs
+        .checked_mul(rules::YELLOW_EGG_POINTS)?
+        .checked_add(blue_eggs.checked_mul(rules::PINK_EGG_POINTS)?)?
+        .checked_add(purple_eggs.checked_mul(rules::PURPLE_EGG_POINTS)?)
 }
 
 /// Calculates total number of eggs
 pub fn total_eggs(yellow_eggs: u32, blue_eggs: u32, purple_eggs: u32) -> u32 {
     yellow_eggs + blue_eggs + purple_eggs
+}
+
+/// Sorts leaderboard entries by score (highest first) and keeps the top `k`.
+/// Ties are broken by proof digest, so the ranking is deterministic.
+pub fn rank_leaderboard(mut entries: Vec<LeaderboardEntry>, k: usize) -> Vec<LeaderboardEntry> {
+    entries.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.proofDigest.cmp(&b.proofDigest)));
+    entries.truncate(k);
+    entries
 }
\ No newline at end of file
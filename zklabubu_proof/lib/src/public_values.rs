@@ -0,0 +1,377 @@
+//! Versioned public values.
+//!
+//! Version 1 (`PublicValuesStruct`) is the original six-field layout with no
+//! version field, recognised by its size. Version 2 structs start with the
+//! same header: `version`, `kind` (full, private or threshold), the `gameId`
+//! domain separator, the `rulesHash` and the `difficulty` preset. Verifiers
+//! decode with [`decode_public_values`] instead of a raw `abi_decode`, so a
+//! layout change is reported instead of misread.
+
+use core::fmt;
+
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_sol_types::SolType;
+
+use crate::kind::{KIND_FULL, KIND_PRIVATE, KIND_THRESHOLD};
+use crate::{Difficulty, PrivatePublicValuesStruct, PublicValuesStruct, PublicValuesV2, ThresholdPublicValuesStruct};
+
+/// Version of the public values committed by the program.
+pub const PUBLIC_VALUES_VERSION: u32 = 2;
+
+/// Domain of the `gameId` separator.
+pub const GAME_DOMAIN: &[u8] = b"zklabubu-sp1.game";
+
+/// `gameId` committed by the program, so proofs of other programs can't pass as ours.
+pub fn game_id() -> B256 {
+    keccak256(GAME_DOMAIN)
+}
+
+/// Public values of any version and kind.
+#[derive(Clone)]
+pub enum PublicValues {
+    V1(PublicValuesStruct),
+    Full(PublicValuesV2),
+    Private(PrivatePublicValuesStruct),
+    Threshold(ThresholdPublicValuesStruct),
+}
+
+#[derive(Debug)]
+pub enum PublicValuesError {
+    /// The bytes don't match the layout announced by the header
+    Abi(alloy_sol_types::Error),
+    UnsupportedVersion(U256),
+    UnknownKind(U256),
+    UnknownDifficulty(u32),
+    /// Committed by another game
+    WrongGame(B256),
+}
+
+impl fmt::Display for PublicValuesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublicValuesError::Abi(err) => write!(f, "invalid public values: {}", err),
+            PublicValuesError::UnsupportedVersion(version) => {
+                write!(f, "unsupported public values version {} (this build reads 1 and {})", version, PUBLIC_VALUES_VERSION)
+            }
+            PublicValuesError::UnknownKind(kind) => write!(f, "unknown proof kind {}", kind),
+            PublicValuesError::UnknownDifficulty(code) => write!(f, "unknown difficulty {}", code),
+            PublicValuesError::WrongGame(game) => write!(f, "public values of another game (game id {})", game),
+        }
+    }
+}
+
+impl std::error::Error for PublicValuesError {}
+
+impl From<alloy_sol_types::Error> for PublicValuesError {
+    fn from(err: alloy_sol_types::Error) -> Self {
+        PublicValuesError::Abi(err)
+    }
+}
+
+/// Decodes public values, dispatching on the version and kind header.
+pub fn decode_public_values(bytes: &[u8]) -> Result<PublicValues, PublicValuesError> {
+    // Version 1 has no header, it is recognised by its size
+    if Some(bytes.len()) == <PublicValuesStruct as SolType>::ENCODED_SIZE {
+        return Ok(PublicValues::V1(PublicValuesStruct::abi_decode(bytes, true)?));
+    }
+
+    let word = |index: usize| bytes.get(index * 32..(index + 1) * 32).map(U256::from_be_slice).unwrap_or_default();
+    let version = word(0);
+    if version != U256::from(PUBLIC_VALUES_VERSION) {
+        return Err(PublicValuesError::UnsupportedVersion(version));
+    }
+
+    let kind = word(1);
+    let values = match u32::try_from(kind) {
+        Ok(KIND_FULL) => PublicValues::Full(PublicValuesV2::abi_decode(bytes, true)?),
+        Ok(KIND_PRIVATE) => PublicValues::Private(PrivatePublicValuesStruct::abi_decode(bytes, true)?),
+        Ok(KIND_THRESHOLD) => PublicValues::Threshold(ThresholdPublicValuesStruct::abi_decode(bytes, true)?),
+        _ => return Err(PublicValuesError::UnknownKind(kind)),
+    };
+
+    let game = values.game_id().unwrap_or_default();
+    if game != game_id() {
+        return Err(PublicValuesError::WrongGame(game));
+    }
+
+    if let Some(code) = values.difficulty_code() {
+        if Difficulty::from_code(code).is_none() {
+            return Err(PublicValuesError::UnknownDifficulty(code));
+        }
+    }
+
+    Ok(values)
+}
+
+impl PublicValues {
+    pub fn version(&self) -> u32 {
+        match self {
+            PublicValues::V1(_) => 1,
+            PublicValues::Full(values) => values.version,
+            PublicValues::Private(values) => values.version,
+            PublicValues::Threshold(values) => values.version,
+        }
+    }
+
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            PublicValues::V1(_) | PublicValues::Full(_) => "full",
+            PublicValues::Private(_) => "private",
+            PublicValues::Threshold(_) => "threshold",
+        }
+    }
+
+    /// Domain separator (not in version 1).
+    pub fn game_id(&self) -> Option<B256> {
+        match self {
+            PublicValues::V1(_) => None,
+            PublicValues::Full(values) => Some(values.gameId),
+            PublicValues::Private(values) => Some(values.gameId),
+            PublicValues::Threshold(values) => Some(values.gameId),
+        }
+    }
+
+    /// Hash of the rules the game was checked against (not in version 1).
+    pub fn rules_hash(&self) -> Option<B256> {
+        match self {
+            PublicValues::V1(_) => None,
+            PublicValues::Full(values) => Some(values.rulesHash),
+            PublicValues::Private(values) => Some(values.rulesHash),
+            PublicValues::Threshold(values) => Some(values.rulesHash),
+        }
+    }
+
+    /// Difficulty preset the game was played on (not in version 1).
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        self.difficulty_code().and_then(Difficulty::from_code)
+    }
+
+    fn difficulty_code(&self) -> Option<u32> {
+        match self {
+            PublicValues::V1(_) => None,
+            PublicValues::Full(values) => Some(values.difficulty),
+            PublicValues::Private(values) => Some(values.difficulty),
+            PublicValues::Threshold(values) => Some(values.difficulty),
+        }
+    }
+
+    /// Exact score, unless hidden by a threshold proof.
+    pub fn score(&self) -> Option<u32> {
+        match self {
+            PublicValues::V1(values) => Some(values.score),
+            PublicValues::Full(values) => Some(values.score),
+            PublicValues::Private(values) => Some(values.score),
+            PublicValues::Threshold(_) => None,
+        }
+    }
+
+    /// Bitmask of the unlocked achievements (full and private proofs, not in version 1).
+    pub fn achievements(&self) -> Option<u32> {
+        match self {
+            PublicValues::Full(values) => Some(values.achievements),
+            PublicValues::Private(values) => Some(values.achievements),
+            PublicValues::V1(_) | PublicValues::Threshold(_) => None,
+        }
+    }
+
+    /// Day of the daily challenge (`YYYYMMDD`), if the game was one.
+    pub fn challenge_date(&self) -> Option<u32> {
+        let date = match self {
+            PublicValues::V1(_) => 0,
+            PublicValues::Full(values) => values.challengeDate,
+            PublicValues::Private(values) => values.challengeDate,
+            PublicValues::Threshold(values) => values.challengeDate,
+        };
+        (date != 0).then_some(date)
+    }
+
+    pub fn player(&self) -> Address {
+        match self {
+            PublicValues::V1(_) => Address::ZERO,
+            PublicValues::Full(values) => values.player,
+            PublicValues::Private(values) => values.player,
+            PublicValues::Threshold(values) => values.player,
+        }
+    }
+
+    /// Service that signed the game's session ticket (zero without a ticket).
+    pub fn ticket_issuer(&self) -> Address {
+        match self {
+            PublicValues::V1(_) => Address::ZERO,
+            PublicValues::Full(values) => values.ticketIssuer,
+            PublicValues::Private(values) => values.ticketIssuer,
+            PublicValues::Threshold(values) => values.ticketIssuer,
+        }
+    }
+
+    pub fn nullifier(&self) -> B256 {
+        match self {
+            PublicValues::V1(_) => B256::ZERO,
+            PublicValues::Full(values) => values.nullifier,
+            PublicValues::Private(values) => values.nullifier,
+            PublicValues::Threshold(values) => values.nullifier,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    const PLAYER: Address = address!("00000000000000000000000000000000000000aa");
+    const ISSUER: Address = address!("00000000000000000000000000000000000000bb");
+
+    fn full() -> PublicValuesV2 {
+        PublicValuesV2 {
+            version: PUBLIC_VALUES_VERSION,
+            kind: KIND_FULL,
+            gameId: game_id(),
+            rulesHash: B256::repeat_byte(1),
+            difficulty: Difficulty::Hard.code(),
+            score: 120,
+            yellowEggs: 10,
+            pinkEggs: 3,
+            purpleEggs: 2,
+            gameTime: 60,
+            lives: 1,
+            rockHits: 2,
+            shieldBlocks: 0,
+            extraLives: 0,
+            achievements: 0b101,
+            player: PLAYER,
+            nullifier: B256::repeat_byte(2),
+            seed: 7,
+            ticketExpiresAt: 1_700_000_000,
+            ticketIssuer: ISSUER,
+            challengeDate: 20250105,
+        }
+    }
+
+    #[test]
+    fn decodes_version_1_by_size() {
+        let values =
+            PublicValuesStruct { score: 50, yellowEggs: 10, pinkEggs: 0, purpleEggs: 0, gameTime: 30, lives: 2 };
+        let bytes = PublicValuesStruct::abi_encode(&values);
+        assert_eq!(bytes.len(), 192);
+
+        let decoded = decode_public_values(&bytes).unwrap();
+        assert!(matches!(decoded, PublicValues::V1(_)));
+        assert_eq!(decoded.version(), 1);
+        assert_eq!(decoded.kind_name(), "full");
+        assert_eq!(decoded.score(), Some(50));
+        assert_eq!((decoded.game_id(), decoded.rules_hash(), decoded.difficulty()), (None, None, None));
+        assert_eq!((decoded.player(), decoded.nullifier()), (Address::ZERO, B256::ZERO));
+        assert_eq!(decoded.challenge_date(), None);
+    }
+
+    #[test]
+    fn decodes_a_full_proof() {
+        let decoded = decode_public_values(&PublicValuesV2::abi_encode(&full())).unwrap();
+        assert!(matches!(decoded, PublicValues::Full(_)));
+        assert_eq!(decoded.version(), PUBLIC_VALUES_VERSION);
+        assert_eq!(decoded.kind_name(), "full");
+        assert_eq!(decoded.game_id(), Some(game_id()));
+        assert_eq!(decoded.rules_hash(), Some(B256::repeat_byte(1)));
+        assert_eq!(decoded.difficulty(), Some(Difficulty::Hard));
+        assert_eq!(decoded.score(), Some(120));
+        assert_eq!(decoded.achievements(), Some(0b101));
+        assert_eq!(decoded.challenge_date(), Some(20250105));
+        assert_eq!((decoded.player(), decoded.ticket_issuer()), (PLAYER, ISSUER));
+        assert_eq!(decoded.nullifier(), B256::repeat_byte(2));
+    }
+
+    #[test]
+    fn decodes_a_private_proof() {
+        let values = PrivatePublicValuesStruct {
+            version: PUBLIC_VALUES_VERSION,
+            kind: KIND_PRIVATE,
+            gameId: game_id(),
+            rulesHash: B256::repeat_byte(1),
+            difficulty: Difficulty::Normal.code(),
+            score: 120,
+            eggsCommitment: B256::repeat_byte(3),
+            gameTime: 60,
+            lives: 1,
+            rockHits: 2,
+            shieldBlocks: 0,
+            extraLives: 0,
+            achievements: 0b11,
+            player: PLAYER,
+            nullifier: B256::repeat_byte(2),
+            seed: 7,
+            ticketExpiresAt: 0,
+            ticketIssuer: Address::ZERO,
+            challengeDate: 0,
+        };
+        let decoded = decode_public_values(&PrivatePublicValuesStruct::abi_encode(&values)).unwrap();
+        assert!(matches!(decoded, PublicValues::Private(_)));
+        assert_eq!(decoded.kind_name(), "private");
+        assert_eq!(decoded.score(), Some(120));
+        assert_eq!(decoded.achievements(), Some(0b11));
+        assert_eq!(decoded.challenge_date(), None);
+        assert_eq!(decoded.ticket_issuer(), Address::ZERO);
+    }
+
+    #[test]
+    fn decodes_a_threshold_proof() {
+        let values = ThresholdPublicValuesStruct {
+            version: PUBLIC_VALUES_VERSION,
+            kind: KIND_THRESHOLD,
+            gameId: game_id(),
+            rulesHash: B256::repeat_byte(1),
+            difficulty: Difficulty::Easy.code(),
+            threshold: 100,
+            passed: true,
+            scoreCommitment: B256::repeat_byte(4),
+            player: PLAYER,
+            nullifier: B256::repeat_byte(2),
+            ticketIssuer: ISSUER,
+            challengeDate: 20240229,
+        };
+        let decoded = decode_public_values(&ThresholdPublicValuesStruct::abi_encode(&values)).unwrap();
+        assert!(matches!(decoded, PublicValues::Threshold(_)));
+        assert_eq!(decoded.kind_name(), "threshold");
+        assert_eq!(decoded.difficulty(), Some(Difficulty::Easy));
+        // The exact score and achievements stay hidden
+        assert_eq!((decoded.score(), decoded.achievements()), (None, None));
+        assert_eq!(decoded.challenge_date(), Some(20240229));
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        for version in [0, 3, PUBLIC_VALUES_VERSION + 1] {
+            let bytes = PublicValuesV2::abi_encode(&PublicValuesV2 { version, ..full() });
+            let result = decode_public_values(&bytes);
+            assert!(
+                matches!(result, Err(PublicValuesError::UnsupportedVersion(v)) if v == U256::from(version)),
+                "version {}",
+                version
+            );
+        }
+        assert!(matches!(decode_public_values(&[]), Err(PublicValuesError::UnsupportedVersion(v)) if v.is_zero()));
+    }
+
+    #[test]
+    fn rejects_other_games_kinds_and_difficulties() {
+        let bytes = PublicValuesV2::abi_encode(&PublicValuesV2 { gameId: B256::ZERO, ..full() });
+        assert!(matches!(decode_public_values(&bytes), Err(PublicValuesError::WrongGame(game)) if game == B256::ZERO));
+
+        let bytes = PublicValuesV2::abi_encode(&PublicValuesV2 { kind: 9, ..full() });
+        let result = decode_public_values(&bytes);
+        assert!(matches!(result, Err(PublicValuesError::UnknownKind(kind)) if kind == U256::from(9)));
+
+        let bytes = PublicValuesV2::abi_encode(&PublicValuesV2 { difficulty: 7, ..full() });
+        assert!(matches!(decode_public_values(&bytes), Err(PublicValuesError::UnknownDifficulty(7))));
+    }
+
+    #[test]
+    fn rejects_a_layout_that_does_not_match_the_kind() {
+        // Full values announced as a threshold proof
+        let bytes = PublicValuesV2::abi_encode(&PublicValuesV2 { kind: KIND_THRESHOLD, ..full() });
+        assert!(matches!(decode_public_values(&bytes), Err(PublicValuesError::Abi(_))));
+
+        let bytes = PublicValuesV2::abi_encode(&full());
+        assert!(matches!(decode_public_values(&bytes[..bytes.len() - 32]), Err(PublicValuesError::Abi(_))));
+    }
+}
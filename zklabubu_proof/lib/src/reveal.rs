@@ -0,0 +1,107 @@
+//! Salted commitments to the egg breakdown.
+//!
+//! A private proof commits `sha256(salt || yellow || pink || purple)` (counts
+//! as big-endian `u32`) instead of the egg counts. The player keeps the salt
+//! and can later disclose the breakdown to a chosen party with an
+//! [`EggReveal`], which that party checks against the committed values.
+
+use alloy_primitives::B256;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{calculate_score, PrivatePublicValuesStruct};
+
+/// Commitment to an egg breakdown.
+pub fn egg_commitment(salt: B256, yellow_eggs: u32, pink_eggs: u32, purple_eggs: u32) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(yellow_eggs.to_be_bytes());
+    hasher.update(pink_eggs.to_be_bytes());
+    hasher.update(purple_eggs.to_be_bytes());
+    B256::from_slice(&hasher.finalize())
+}
+
+/// Disclosed egg breakdown of a private proof.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EggReveal {
+    pub salt: B256,
+    pub yellow_eggs: u32,
+    pub pink_eggs: u32,
+    pub purple_eggs: u32,
+}
+
+impl EggReveal {
+    pub fn commitment(&self) -> B256 {
+        egg_commitment(self.salt, self.yellow_eggs, self.pink_eggs, self.purple_eggs)
+    }
+
+    /// Checks the breakdown against the public values of a private proof:
+    /// it must open the commitment and add up to the committed score.
+    pub fn verify(&self, public_values: &PrivatePublicValuesStruct) -> bool {
+        self.commitment() == public_values.eggsCommitment
+            && calculate_score(self.yellow_eggs, self.pink_eggs, self.purple_eggs) == Some(public_values.score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Address;
+
+    use crate::kind::KIND_PRIVATE;
+    use crate::{game_id, Difficulty, PUBLIC_VALUES_VERSION};
+
+    fn reveal() -> EggReveal {
+        EggReveal { salt: B256::repeat_byte(1), yellow_eggs: 10, pink_eggs: 3, purple_eggs: 2 }
+    }
+
+    // Public values of a private proof of the `reveal()` game
+    fn public_values() -> PrivatePublicValuesStruct {
+        PrivatePublicValuesStruct {
+            version: PUBLIC_VALUES_VERSION,
+            kind: KIND_PRIVATE,
+            gameId: game_id(),
+            rulesHash: B256::ZERO,
+            difficulty: Difficulty::Normal.code(),
+            score: calculate_score(10, 3, 2).unwrap(),
+            eggsCommitment: egg_commitment(B256::repeat_byte(1), 10, 3, 2),
+            gameTime: 60,
+            lives: 1,
+            rockHits: 2,
+            shieldBlocks: 0,
+            extraLives: 0,
+            achievements: 0,
+            player: Address::ZERO,
+            nullifier: B256::ZERO,
+            seed: 7,
+            ticketExpiresAt: 0,
+            ticketIssuer: Address::ZERO,
+            challengeDate: 0,
+        }
+    }
+
+    #[test]
+    fn reveal_opens_the_commitment() {
+        assert_eq!(reveal().commitment(), public_values().eggsCommitment);
+        assert!(reveal().verify(&public_values()));
+    }
+
+    #[test]
+    fn rejects_a_wrong_salt() {
+        let tampered = EggReveal { salt: B256::repeat_byte(2), ..reveal() };
+        assert!(!tampered.verify(&public_values()));
+    }
+
+    #[test]
+    fn rejects_wrong_counts() {
+        // Same score, different breakdown
+        let tampered = EggReveal { yellow_eggs: 12, pink_eggs: 2, ..reveal() };
+        assert_eq!(calculate_score(12, 2, 2), Some(public_values().score));
+        assert!(!tampered.verify(&public_values()));
+
+        // A breakdown that opens the commitment but not the committed score
+        let public_values = PrivatePublicValuesStruct { score: 1000, ..public_values() };
+        assert!(!reveal().verify(&public_values));
+    }
+}
@@ -0,0 +1,273 @@
+//! Upper bounds derived from the game rules.
+//!
+//! The program does not replay the game, but a summary still has to be
+//! physically possible under the [`GameConfig`]: items spawn at most every
+//! `minSpawnIntervalMs` (0.4 seconds by default), at most `maxItemsPerSpawn`
+//! (3) per spawn, and only `purpleEgg` percent (10%) of them are purple eggs.
+//! Lives follow from the rocks that hit the player and the extra lives picked up.
+
+use core::fmt;
+
+use crate::{calculate_score, GameConfig};
+
+/// Points of each egg colour.
+pub const YELLOW_EGG_POINTS: u32 = 5;
+pub const PINK_EGG_POINTS: u32 = 10;
+pub const PURPLE_EGG_POINTS: u32 = 20;
+
+/// Standard deviations of slack on the purple egg count.
+pub const PURPLE_TOLERANCE_SIGMAS: u32 = 4;
+
+/// Lives at the start of a game with the default config.
+pub const START_LIVES: u32 = 3;
+
+/// Longest game in seconds: a game can't outlast its session ticket (2 hours by default).
+pub const MAX_GAME_TIME: u32 = 2 * 60 * 60;
+
+/// Why a game summary cannot come from a real game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Implausible {
+    GameTooLong { game_time: u32, max: u32 },
+    ScoreOverflow,
+    TooManyLives { lives: u32, max: u32 },
+    TooManyEggs { eggs: u64, max: u64 },
+    TooManyPurpleEggs { purple_eggs: u32, max: u64 },
+    TooManyRocks { rocks: u64, max: u64 },
+    TooManyPowerUps { extra_lives: u32, max: u32 },
+    LivesMismatch { lives: u32, expected: Option<u32> },
+}
+
+impl fmt::Display for Implausible {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Implausible::GameTooLong { game_time, max } => {
+                write!(f, "{} second game, at most {} seconds are possible", game_time, max)
+            }
+            Implausible::ScoreOverflow => write!(f, "the egg counts overflow the score"),
+            Implausible::TooManyLives { lives, max } => write!(f, "{} lives, at most {} are possible", lives, max),
+            Implausible::TooManyEggs { eggs, max } => {
+                write!(f, "{} eggs collected, at most {} can drop in this game time", eggs, max)
+            }
+            Implausible::TooManyPurpleEggs { purple_eggs, max } => {
+                write!(f, "{} purple eggs collected, at most {} are plausible in this game time", purple_eggs, max)
+            }
+            Implausible::TooManyRocks { rocks, max } => {
+                write!(f, "{} rocks hit or blocked, at most {} items can drop in this game time", rocks, max)
+            }
+            Implausible::TooManyPowerUps { extra_lives, max } => {
+                write!(f, "{} extra lives gained, at most {} power-ups drop for this score", extra_lives, max)
+            }
+            Implausible::LivesMismatch { lives, expected: Some(expected) } => {
+                write!(f, "{} lives reported, the rock hits and extra lives leave {}", lives, expected)
+            }
+            Implausible::LivesMismatch { lives, expected: None } => {
+                write!(f, "{} lives reported, but the game ended before all rock hits", lives)
+            }
+        }
+    }
+}
+
+/// Most spawns in a game of `game_time` seconds (the time is truncated, so up to one more second was played).
+pub fn max_spawns(config: &GameConfig, game_time: u32) -> u64 {
+    // The first spawn happens at the start of the game
+    (game_time as u64 + 1) * 1000 / config.difficulty.min_spawn_interval_ms as u64 + 1
+}
+
+/// Most eggs and rocks dropped in a game of `game_time` seconds.
+pub fn max_items(config: &GameConfig, game_time: u32) -> u64 {
+    max_spawns(config, game_time) * config.difficulty.max_items_per_spawn as u64
+}
+
+/// Most purple eggs that plausibly drop in a game of `game_time` seconds.
+///
+/// The expected count plus `PURPLE_TOLERANCE_SIGMAS` standard deviations of
+/// the binomial distribution, and a small constant for very short games.
+pub fn max_purple_eggs(config: &GameConfig, game_time: u32) -> u64 {
+    let items = max_items(config, game_time);
+    let purple_percent = config.spawn_chances.purple_egg as u64;
+    let expected = items * purple_percent / 100;
+    // sd = sqrt(n * p * (1 - p)), rounded up
+    let variance_percent = items * purple_percent * (100 - purple_percent);
+    let sigma = isqrt(variance_percent) / 100 + 1;
+    expected + PURPLE_TOLERANCE_SIGMAS as u64 * sigma + 2
+}
+
+/// Checks that the lives and egg counts can coexist with the game time, and that the score fits.
+pub fn check_plausibility(
+    config: &GameConfig,
+    yellow_eggs: u32,
+    blue_eggs: u32,
+    purple_eggs: u32,
+    game_time: u32,
+    lives: u32,
+) -> Result<(), Implausible> {
+    if game_time > MAX_GAME_TIME {
+        return Err(Implausible::GameTooLong { game_time, max: MAX_GAME_TIME });
+    }
+
+    if lives > config.max_lives {
+        return Err(Implausible::TooManyLives { lives, max: config.max_lives });
+    }
+
+    let eggs = yellow_eggs as u64 + blue_eggs as u64 + purple_eggs as u64;
+    let max = max_items(config, game_time);
+    if eggs > max {
+        return Err(Implausible::TooManyEggs { eggs, max });
+    }
+
+    let max = max_purple_eggs(config, game_time);
+    if purple_eggs as u64 > max {
+        return Err(Implausible::TooManyPurpleEggs { purple_eggs, max });
+    }
+
+    if calculate_score(yellow_eggs, blue_eggs, purple_eggs).is_none() {
+        return Err(Implausible::ScoreOverflow);
+    }
+
+    Ok(())
+}
+
+/// Lives at the end of a game, or `None` if the rock hits would have ended it earlier (or the lives overflow).
+///
+/// `extra_lives` counts the pickups that added a life (at the lives cap a pickup adds nothing),
+/// and `rock_hits` the rocks that hit the player without a shield.
+pub fn final_lives(config: &GameConfig, rock_hits: u32, extra_lives: u32) -> Option<u32> {
+    config.start_lives.checked_add(extra_lives)?.checked_sub(rock_hits)
+}
+
+/// Checks that the final lives follow from the rock hits and extra lives.
+pub fn check_lives(
+    config: &GameConfig,
+    score: u32,
+    game_time: u32,
+    lives: u32,
+    rock_hits: u32,
+    shield_blocks: u32,
+    extra_lives: u32,
+) -> Result<(), Implausible> {
+    // Each rock is one dropped item, blocked or not
+    let rocks = rock_hits as u64 + shield_blocks as u64;
+    let max = max_items(config, game_time);
+    if rocks > max {
+        return Err(Implausible::TooManyRocks { rocks, max });
+    }
+
+    // Extra lives are power-ups, which drop once per `powerUpScoreStep` points
+    let max = score / config.power_up_score_step;
+    if extra_lives > max {
+        return Err(Implausible::TooManyPowerUps { extra_lives, max });
+    }
+
+    let expected = final_lives(config, rock_hits, extra_lives);
+    if expected != Some(lives) {
+        return Err(Implausible::LivesMismatch { lives, expected });
+    }
+
+    Ok(())
+}
+
+// Integer square root (rounded down)
+fn isqrt(n: u64) -> u64 {
+    let mut root = (n as f64).sqrt() as u64;
+    while root * root > n {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= n {
+        root += 1;
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_follow_the_spawn_rate() {
+        let config = GameConfig::default();
+        // One spawn at the start, then one every 0.4 seconds of the (up to) 1 second played
+        assert_eq!(max_spawns(&config, 0), 3);
+        assert_eq!(max_spawns(&config, 59), 151);
+        assert_eq!(max_items(&config, 59), 453);
+        assert_eq!(max_purple_eggs(&config, 59), 45 + 4 * 7 + 2);
+    }
+
+    #[test]
+    fn check_plausibility_rejects_impossible_counts() {
+        let config = GameConfig::default();
+        assert_eq!(check_plausibility(&config, 100, 50, 20, 59, 3), Ok(()));
+        assert_eq!(check_plausibility(&config, 0, 0, 0, 59, 6), Err(Implausible::TooManyLives { lives: 6, max: 5 }));
+        assert_eq!(
+            check_plausibility(&config, 400, 50, 10, 59, 3),
+            Err(Implausible::TooManyEggs { eggs: 460, max: 453 })
+        );
+        assert_eq!(
+            check_plausibility(&config, 0, 0, 76, 59, 3),
+            Err(Implausible::TooManyPurpleEggs { purple_eggs: 76, max: 75 })
+        );
+        // The 10,000 purple eggs in a 5 second game
+        assert!(matches!(check_plausibility(&config, 0, 0, 10_000, 5, 3), Err(Implausible::TooManyEggs { .. })));
+    }
+
+    #[test]
+    fn check_plausibility_bounds_the_game_time() {
+        let config = GameConfig::default();
+        assert_eq!(check_plausibility(&config, 0, 0, 0, MAX_GAME_TIME, 3), Ok(()));
+        assert_eq!(
+            check_plausibility(&config, 0, 0, 0, MAX_GAME_TIME + 1, 3),
+            Err(Implausible::GameTooLong { game_time: MAX_GAME_TIME + 1, max: MAX_GAME_TIME })
+        );
+        // Without the cap, a long enough game time would let any egg count through
+        assert_eq!(
+            check_plausibility(&config, u32::MAX, 0, 0, u32::MAX, 3),
+            Err(Implausible::GameTooLong { game_time: u32::MAX, max: MAX_GAME_TIME })
+        );
+    }
+
+    #[test]
+    fn calculate_score_rejects_an_overflow() {
+        assert_eq!(calculate_score(10, 3, 2), Some(10 * 5 + 3 * 10 + 2 * 20));
+        assert_eq!(calculate_score(u32::MAX / 5, 0, 0), Some(u32::MAX / 5 * 5));
+        assert_eq!(calculate_score(u32::MAX / 5 + 1, 0, 0), None);
+        assert_eq!(calculate_score(0, 0, u32::MAX / 20 + 1), None);
+        // Each term fits, the sum doesn't
+        assert_eq!(calculate_score(u32::MAX / 5, u32::MAX / 10, 0), None);
+    }
+
+    #[test]
+    fn check_lives_follows_rock_hits_and_extra_lives() {
+        let config = GameConfig::default();
+        assert_eq!(final_lives(&config, 2, 1), Some(2));
+        assert_eq!(final_lives(&config, 5, 1), None);
+        // Lives that overflow are rejected, not wrapped around
+        assert_eq!(final_lives(&config, 0, u32::MAX), None);
+        assert_eq!(final_lives(&config, 3, u32::MAX - 3), Some(u32::MAX - 3));
+
+        assert_eq!(check_lives(&config, 300, 59, 2, 2, 4, 1), Ok(()));
+        assert_eq!(
+            check_lives(&config, 300, 59, 3, 2, 4, 1),
+            Err(Implausible::LivesMismatch { lives: 3, expected: Some(2) })
+        );
+        assert_eq!(
+            check_lives(&config, 300, 59, 0, 5, 0, 1),
+            Err(Implausible::LivesMismatch { lives: 0, expected: None })
+        );
+        // One power-up per 100 points
+        assert_eq!(
+            check_lives(&config, 199, 59, 4, 0, 0, 2),
+            Err(Implausible::TooManyPowerUps { extra_lives: 2, max: 1 })
+        );
+        assert_eq!(
+            check_lives(&config, 300, 0, 0, 8, 2, 0),
+            Err(Implausible::TooManyRocks { rocks: 10, max: 3 * 3 })
+        );
+    }
+
+    #[test]
+    fn isqrt_rounds_down() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(407_700), 638);
+    }
+}
@@ -0,0 +1,169 @@
+//! Session tickets: the game service issues a seed, an expiry and a nonce for
+//! every game and signs them, so a proof can only be made for a session the
+//! service started. The nonce is committed as a nullifier.
+//!
+//! The issuer is pinned: the program is given the service address and refuses
+//! a ticket signed by anyone else, and commits that address as `ticketIssuer`
+//! for the verifier to check.
+
+use core::fmt;
+
+use alloy_primitives::{keccak256, Address, Bytes, Signature, SignatureError, B256, U64};
+use alloy_sol_types::SolType;
+use serde::{Deserialize, Serialize};
+
+use crate::SessionTicket;
+
+#[derive(Debug)]
+pub enum TicketError {
+    Signature(SignatureError),
+    /// Signed by another key than the pinned service
+    WrongIssuer { expected: Address, actual: Address },
+}
+
+impl fmt::Display for TicketError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TicketError::Signature(err) => write!(f, "invalid ticket signature: {}", err),
+            TicketError::WrongIssuer { expected, actual } => {
+                write!(f, "ticket issued by {}, not by the game service {}", actual, expected)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TicketError {}
+
+impl From<SignatureError> for TicketError {
+    fn from(err: SignatureError) -> Self {
+        TicketError::Signature(err)
+    }
+}
+
+/// Session ticket with the service signature, as sent to the browser.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedTicket {
+    /// Seed of the game's random spawn sequence
+    pub seed: U64,
+    /// Unix time (seconds) after which the ticket can no longer be used
+    pub expires_at: u64,
+    /// Unique per ticket, committed as the game's nullifier
+    pub nonce: B256,
+    /// Day of a daily challenge (`YYYYMMDD`), 0 for a regular game
+    #[serde(default)]
+    pub challenge_date: u32,
+    /// Service signature over the ticket digest (65 bytes, EIP-191)
+    pub signature: Bytes,
+}
+
+impl SignedTicket {
+    /// The signed part of the ticket.
+    pub fn ticket(&self) -> SessionTicket {
+        SessionTicket {
+            seed: self.seed.to::<u64>(),
+            expiresAt: self.expires_at,
+            nonce: self.nonce,
+            challengeDate: self.challenge_date,
+        }
+    }
+
+    /// Recovers the address of the service that issued the ticket.
+    pub fn recover_issuer(&self) -> Result<Address, SignatureError> {
+        let signature = Signature::try_from(self.signature.as_ref())?;
+        signature.recover_address_from_msg(ticket_digest(&self.ticket()))
+    }
+
+    /// Fails unless the ticket was signed by `issuer`.
+    pub fn verify_issuer(&self, issuer: Address) -> Result<(), TicketError> {
+        let actual = self.recover_issuer()?;
+        if actual != issuer {
+            return Err(TicketError::WrongIssuer { expected: issuer, actual });
+        }
+        Ok(())
+    }
+
+    /// Whether the ticket can no longer be used at unix time `now` (seconds).
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires_at <= now
+    }
+}
+
+/// Digest of a session ticket, signed by the service with EIP-191.
+pub fn ticket_digest(ticket: &SessionTicket) -> B256 {
+    keccak256(SessionTicket::abi_encode(ticket))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::eip191_hash_message;
+    use k256::ecdsa::SigningKey;
+
+    fn signed(key: &SigningKey, seed: u64, expires_at: u64, nonce: B256) -> SignedTicket {
+        let ticket = SessionTicket { seed, expiresAt: expires_at, nonce, challengeDate: 0 };
+        let hash = eip191_hash_message(ticket_digest(&ticket));
+        let (signature, recovery_id) = key.sign_prehash_recoverable(hash.as_slice()).unwrap();
+        SignedTicket {
+            seed: U64::from(seed),
+            expires_at,
+            nonce,
+            challenge_date: 0,
+            signature: Bytes::from(Signature::from((signature, recovery_id)).as_bytes()),
+        }
+    }
+
+    #[test]
+    fn recovers_the_issuing_service() {
+        let key = SigningKey::from_slice(&[9; 32]).unwrap();
+        let ticket = signed(&key, 42, 1_700_000_000, B256::repeat_byte(1));
+        assert_eq!(ticket.recover_issuer().unwrap(), Address::from_private_key(&key));
+    }
+
+    #[test]
+    fn tampered_nonce_recovers_another_issuer() {
+        let key = SigningKey::from_slice(&[9; 32]).unwrap();
+        let mut ticket = signed(&key, 42, 1_700_000_000, B256::repeat_byte(1));
+        ticket.nonce = B256::repeat_byte(2);
+        assert_ne!(ticket.recover_issuer().unwrap(), Address::from_private_key(&key));
+    }
+
+    #[test]
+    fn verify_issuer_rejects_another_key() {
+        let service = SigningKey::from_slice(&[9; 32]).unwrap();
+        let other = SigningKey::from_slice(&[10; 32]).unwrap();
+        let ticket = signed(&other, 42, 1_700_000_000, B256::repeat_byte(1));
+
+        let expected = Address::from_private_key(&service);
+        match ticket.verify_issuer(expected) {
+            Err(TicketError::WrongIssuer { expected: pinned, actual }) => {
+                assert_eq!(pinned, expected);
+                assert_eq!(actual, Address::from_private_key(&other));
+            }
+            other => panic!("expected WrongIssuer, got {:?}", other),
+        }
+        ticket.verify_issuer(Address::from_private_key(&other)).unwrap();
+    }
+
+    #[test]
+    fn verify_issuer_rejects_a_tampered_nonce() {
+        let service = SigningKey::from_slice(&[9; 32]).unwrap();
+        let mut ticket = signed(&service, 42, 1_700_000_000, B256::repeat_byte(1));
+        ticket.verify_issuer(Address::from_private_key(&service)).unwrap();
+
+        ticket.nonce = B256::repeat_byte(2);
+        assert!(matches!(
+            ticket.verify_issuer(Address::from_private_key(&service)),
+            Err(TicketError::WrongIssuer { .. })
+        ));
+    }
+
+    #[test]
+    fn expires_at_the_expiry_time() {
+        let service = SigningKey::from_slice(&[9; 32]).unwrap();
+        let ticket = signed(&service, 42, 1_700_000_000, B256::repeat_byte(1));
+        assert!(!ticket.is_expired(1_699_999_999));
+        assert!(ticket.is_expired(1_700_000_000));
+        assert!(ticket.is_expired(1_700_000_001));
+    }
+}
@@ -6,7 +6,13 @@
 sp1_zkvm::entrypoint!(main);
 
 use alloy_sol_types::SolType;
-use zklabubu_proof_lib::{calculate_score, total_eggs, PublicValuesStruct};
+use alloy_primitives::{Address, B256};
+use zklabubu_proof_lib::{
+    calculate_score, check_lives, check_plausibility, egg_commitment, game_id, is_valid_challenge_date,
+    passes_threshold, recover_player, score_commitment, total_eggs, Difficulty, PublicValuesV2, GameConfig,
+    GameEvent, GameSession, LedgerCounts, LedgerReplay, PrivatePublicValuesStruct, ProofKind, ScoreOpening,
+    SignedTicket, ThresholdPublicValuesStruct, PUBLIC_VALUES_VERSION,
+};
 
 pub fn main() {
     // Read input data
@@ -16,31 +22,198 @@ pub fn main() {
     let reported_score = sp1_zkvm::io::read::<u32>();
     let game_time = sp1_zkvm::io::read::<u32>();
     let lives = sp1_zkvm::io::read::<u32>();
+    let rock_hits = sp1_zkvm::io::read::<u32>();
+    let shield_blocks = sp1_zkvm::io::read::<u32>();
+    let extra_lives = sp1_zkvm::io::read::<u32>();
+    let player_signature = sp1_zkvm::io::read::<Option<Vec<u8>>>();
+    let ticket = sp1_zkvm::io::read::<Option<SignedTicket>>();
+    let ticket_issuer = sp1_zkvm::io::read::<Address>();
+    let proof_kind = sp1_zkvm::io::read::<ProofKind>();
+    let ledger = sp1_zkvm::io::read::<Option<Vec<GameEvent>>>();
+    let config = sp1_zkvm::io::read::<GameConfig>();
+    let difficulty = sp1_zkvm::io::read::<Difficulty>();
     
-    // Verify score
-    let calculated_score = calculate_score(n_yellow, n_pink, n_purple);
+    // The preset adjusts the config, and the game must have been played under a playable one
+    let config = difficulty.apply(&config);
+    if let Err(err) = config.validate() {
+        panic!("invalid game config: {}", err);
+    }
+    
+    // Reject summaries that no real game can produce
+    if let Err(err) = check_plausibility(&config, n_yellow, n_pink, n_purple, game_time, lives) {
+        panic!("implausible game: {}", err);
+    }
+    
+    // Verify score (check_plausibility rejected egg counts that overflow it)
+    let calculated_score = calculate_score(n_yellow, n_pink, n_purple).expect("score overflows");
     let score_is_valid = reported_score == calculated_score;
     
+    // The final lives must follow from the rock hits and extra lives
+    if let Err(err) = check_lives(&config, calculated_score, game_time, lives, rock_hits, shield_blocks, extra_lives) {
+        panic!("implausible game: {}", err);
+    }
+    
+    // Replay the event ledger and evaluate the achievements (none without a ledger)
+    let achievements = match &ledger {
+        Some(events) => {
+            let replay = LedgerReplay::replay(events).unwrap_or_else(|err| panic!("invalid ledger: {}", err));
+            let summary = LedgerCounts {
+                yellow_eggs: n_yellow,
+                pink_eggs: n_pink,
+                purple_eggs: n_purple,
+                rock_hits,
+                shield_blocks,
+                extra_lives,
+            };
+            if let Err(err) = replay.check_summary(&summary, game_time) {
+                panic!("invalid ledger: {}", err);
+            }
+            replay.unlocked()
+        }
+        None => 0,
+    };
+    
     // Calculate total number of eggs collected
     let total_collected = total_eggs(n_yellow, n_pink, n_purple);
     
+    // The session ticket must be signed by the pinned service, committed for the verifier to check
+    let ticket_issuer = match &ticket {
+        Some(ticket) => {
+            if let Err(err) = ticket.verify_issuer(ticket_issuer) {
+                panic!("invalid session ticket: {}", err);
+            }
+            ticket_issuer
+        }
+        None => Address::ZERO,
+    };
+    let session_ticket = ticket.as_ref().map(|ticket| ticket.ticket());
+    let nonce = session_ticket.as_ref().map_or(B256::ZERO, |ticket| ticket.nonce);
+    // Daily challenge tickets carry the day, the service derived the seed from it
+    let challenge_date = session_ticket.as_ref().map_or(0, |ticket| ticket.challengeDate);
+    assert!(challenge_date == 0 || is_valid_challenge_date(challenge_date), "invalid daily challenge date {}", challenge_date);
+    
+    // Recover the player who signed the session (zero address if unsigned)
+    let session = GameSession {
+        score: calculated_score,
+        yellowEggs: n_yellow,
+        pinkEggs: n_pink,
+        purpleEggs: n_purple,
+        gameTime: game_time,
+        lives,
+        rockHits: rock_hits,
+        shieldBlocks: shield_blocks,
+        extraLives: extra_lives,
+        nonce,
+    };
+    let player = match player_signature {
+        Some(signature) => recover_player(&session, &signature).expect("invalid player signature"),
+        None => Address::ZERO,
+    };
+    
+    // Header of the versioned public values
+    let kind = proof_kind.code();
+    let game_id = game_id();
+    let rules_hash = config.rules_hash();
+    
     // Encode results and provide as output
-    let public_values = PublicValuesStruct {
+    let public_values = PublicValuesV2 {
+        version: PUBLIC_VALUES_VERSION,
+        kind,
+        gameId: game_id,
+        rulesHash: rules_hash,
+        difficulty: difficulty.code(),
         score: calculated_score,
         yellowEggs: n_yellow,
         pinkEggs: n_pink,
         purpleEggs: n_purple,
         gameTime: game_time,
-        lives: lives
+        lives: lives,
+        rockHits: rock_hits,
+        shieldBlocks: shield_blocks,
+        extraLives: extra_lives,
+        achievements,
+        player,
+        nullifier: nonce,
+        seed: session_ticket.as_ref().map_or(0, |ticket| ticket.seed),
+        ticketExpiresAt: session_ticket.as_ref().map_or(0, |ticket| ticket.expiresAt),
+        ticketIssuer: ticket_issuer,
+        challengeDate: challenge_date,
     };
     
-    // Debug output
-    println!("Egg counts: Yellow={}, Pink={}, Purple={}", n_yellow, n_pink, n_purple);
-    println!("Reported score: {}, Calculated score: {}", reported_score, calculated_score);
-    println!("Score verification: {}", if score_is_valid { "SUCCESS" } else { "FAILED" });
-    println!("Total eggs collected: {}", total_collected);
+    // Debug output (only what the public values disclose: a private proof hides the
+    // egg counts, a threshold proof the whole game summary)
+    let hides_summary = matches!(proof_kind, ProofKind::Threshold { .. });
+    if proof_kind == ProofKind::Full {
+        println!("Egg counts: Yellow={}, Pink={}, Purple={}", n_yellow, n_pink, n_purple);
+        println!("Total eggs collected: {}", total_collected);
+    }
+    if !hides_summary {
+        println!("Reported score: {}, Calculated score: {}", reported_score, calculated_score);
+        println!("Score verification: {}", if score_is_valid { "SUCCESS" } else { "FAILED" });
+        println!("Rock hits: {}, Shield blocks: {}, Extra lives: {}", rock_hits, shield_blocks, extra_lives);
+        println!("Achievements: {:#b}", achievements);
+    }
+    println!("Difficulty: {}", difficulty.name());
+    println!("Player: {}", player);
+    println!("Ticket issuer: {}, Nullifier: {}", ticket_issuer, nonce);
+    if challenge_date != 0 {
+        println!("Daily challenge: {}", challenge_date);
+    }
     
     // Process as output (in a format that can be verified in Solidity)
-    let bytes = PublicValuesStruct::abi_encode(&public_values);
+    let bytes = match proof_kind {
+        ProofKind::Full => PublicValuesV2::abi_encode(&public_values),
+        ProofKind::Private { salt } => {
+            // The egg breakdown is only committed, the player can reveal it with the salt
+            let private_values = PrivatePublicValuesStruct {
+                version: PUBLIC_VALUES_VERSION,
+                kind,
+                gameId: game_id,
+                rulesHash: rules_hash,
+                difficulty: difficulty.code(),
+                score: public_values.score,
+                eggsCommitment: egg_commitment(salt, n_yellow, n_pink, n_purple),
+                gameTime: public_values.gameTime,
+                lives: public_values.lives,
+                rockHits: public_values.rockHits,
+                shieldBlocks: public_values.shieldBlocks,
+                extraLives: public_values.extraLives,
+                achievements: public_values.achievements,
+                player: public_values.player,
+                nullifier: public_values.nullifier,
+                seed: public_values.seed,
+                ticketExpiresAt: public_values.ticketExpiresAt,
+                ticketIssuer: public_values.ticketIssuer,
+                challengeDate: challenge_date,
+            };
+            PrivatePublicValuesStruct::abi_encode(&private_values)
+        }
+        ProofKind::Threshold { threshold, salt } => {
+            // Only the threshold result is public, the score is hidden in a commitment
+            let opening = ScoreOpening {
+                salt,
+                score: calculated_score,
+                yellowEggs: n_yellow,
+                pinkEggs: n_pink,
+                purpleEggs: n_purple,
+            };
+            let threshold_values = ThresholdPublicValuesStruct {
+                version: PUBLIC_VALUES_VERSION,
+                kind,
+                gameId: game_id,
+                rulesHash: rules_hash,
+                difficulty: difficulty.code(),
+                threshold,
+                passed: passes_threshold(calculated_score, threshold),
+                scoreCommitment: score_commitment(&opening),
+                player,
+                nullifier: nonce,
+                ticketIssuer: ticket_issuer,
+                challengeDate: challenge_date,
+            };
+            println!("Threshold {}: {}", threshold, if threshold_values.passed { "PASSED" } else { "NOT PASSED" });
+            ThresholdPublicValuesStruct::abi_encode(&threshold_values)
+        }
+    };
     sp1_zkvm::io::commit_slice(&bytes);
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 fn main() {
-    // Automatically build the program directory
+    // Automatically build the program directories
     sp1_build::build_program("../program");
+    sp1_build::build_program("../aggregation");
 }
\ No newline at end of file
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use alloy_primitives::{Address, B256};
+use alloy_sol_types::SolType;
+use clap::Parser;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sp1_sdk::{HashableKey, ProverClient, SP1Proof, SP1ProofWithPublicValues, SP1Stdin};
+use zklabubu_proof_lib::{
+    decode_public_values, format_challenge_date, Difficulty, GameConfig, LeaderboardStruct, PublicValues,
+    PUBLIC_VALUES_VERSION,
+};
+use zklabubu_proof_script::{
+    key_cache::setup_cached,
+    ZKLABUBU_AGGREGATION_ELF, ZKLABUBU_AGGREGATION_PROGRAM, ZKLABUBU_PROOF_ELF, ZKLABUBU_PROOF_PROGRAM,
+};
+
+/// Command line arguments
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    #[clap(long)]
+    execute: bool,
+
+    #[clap(long)]
+    prove: bool,
+
+    /// JSONL file with one game per line: {"proof": "batch-proofs/game-001.bin"}
+    #[clap(long)]
+    input: PathBuf,
+
+    /// Number of games kept on the leaderboard
+    #[clap(long, default_value = "10")]
+    top_k: u32,
+
+    /// Where the leaderboard proof is saved
+    #[clap(long, default_value = "leaderboard_proof.bin")]
+    output: PathBuf,
+
+    /// Address of the game service every game must be ticketed by (zero ranks games without a ticket)
+    #[clap(long, default_value_t = Address::ZERO)]
+    ticket_issuer: Address,
+
+    /// Rank games without a ticket (they have no nullifier, so nothing stops the same game twice)
+    #[clap(long)]
+    allow_untracked: bool,
+
+    /// Rules hash every game must be played under (the default config on normal difficulty if unset)
+    #[clap(long)]
+    rules_hash: Option<B256>,
+
+    /// Ignore the cached SP1 keys and run the setup again
+    #[clap(long)]
+    rebuild_keys: bool,
+}
+
+/// One game of the tournament.
+#[derive(Deserialize, Debug)]
+struct AggregateLine {
+    /// Compressed game proof (as written by `prove-batch`)
+    proof: PathBuf,
+}
+
+// Stop on a bad input line
+fn exit_at_line(line_number: usize, message: String) -> ! {
+    eprintln!("Error: line {}: {}", line_number + 1, message);
+    std::process::exit(1);
+}
+
+fn main() {
+    // Setup logger
+    sp1_sdk::utils::setup_logger();
+    dotenv::dotenv().ok();
+
+    let args = Args::parse();
+    let expected_rules = args.rules_hash.unwrap_or_else(|| GameConfig::default().rules_hash());
+
+    if args.execute == args.prove {
+        eprintln!("Error: You must specify either --execute or --prove");
+        std::process::exit(1);
+    }
+
+    let contents = fs::read_to_string(&args.input).unwrap_or_else(|err| {
+        eprintln!("Error: could not read {}: {}", args.input.display(), err);
+        std::process::exit(1);
+    });
+
+    // Load the game proofs
+    let mut proofs = Vec::new();
+    let mut digests = HashMap::new();
+    let mut nullifiers = HashMap::new();
+    let mut challenge_date = None;
+    let mut difficulty = None;
+    for (line_number, line) in contents.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let game: AggregateLine = serde_json::from_str(line)
+            .unwrap_or_else(|err| exit_at_line(line_number, format!("invalid JSON: {}", err)));
+        let proof = SP1ProofWithPublicValues::load(&game.proof)
+            .unwrap_or_else(|err| exit_at_line(line_number, format!("could not load {}: {}", game.proof.display(), err)));
+
+        // The aggregation program refuses a game submitted twice, fail early
+        let values = decode_public_values(proof.public_values.as_slice())
+            .unwrap_or_else(|err| exit_at_line(line_number, err.to_string()));
+        if !matches!(values, PublicValues::Full(_)) {
+            exit_at_line(line_number, format!("only full version {} proofs can be ranked (got a version {} {} proof)", PUBLIC_VALUES_VERSION, values.version(), values.kind_name()));
+        }
+        let digest: [u8; 32] = Sha256::digest(proof.public_values.as_slice()).into();
+        if let Some(first) = digests.insert(digest, line_number) {
+            exit_at_line(line_number, format!("same proof as line {}", first + 1));
+        }
+        let nullifier = values.nullifier();
+        if nullifier.is_zero() && !args.allow_untracked {
+            exit_at_line(line_number, "game without a ticket has no nullifier (use --allow-untracked)".to_string());
+        }
+        if !nullifier.is_zero() {
+            if let Some(first) = nullifiers.insert(nullifier, line_number) {
+                exit_at_line(
+                    line_number,
+                    format!("game already submitted on line {} (nullifier {})", first + 1, nullifier),
+                );
+            }
+        }
+
+        // The aggregation program only ranks games ticketed by the pinned service
+        let issuer = values.ticket_issuer();
+        if issuer != args.ticket_issuer {
+            exit_at_line(line_number, format!("ticket issuer {} is not {}", issuer, args.ticket_issuer));
+        }
+
+        // A daily leaderboard only ranks games of the same day
+        let date = values.challenge_date().unwrap_or_default();
+        let (first_line, first_date) = *challenge_date.get_or_insert((line_number, date));
+        if date != first_date {
+            exit_at_line(
+                line_number,
+                format!("daily challenge {} differs from line {} ({})", date, first_line + 1, first_date),
+            );
+        }
+
+        // The aggregation program only ranks games played under the pinned rules
+        let rules = values.rules_hash().unwrap_or_default();
+        if rules != expected_rules {
+            exit_at_line(line_number, format!("rules hash {} is not {}", rules, expected_rules));
+        }
+
+        // Leaderboards are split per difficulty
+        let preset = values.difficulty().unwrap_or_default();
+        let (first_line, first_preset) = *difficulty.get_or_insert((line_number, preset));
+        if preset != first_preset {
+            exit_at_line(
+                line_number,
+                format!("difficulty {} differs from line {} ({})", preset.name(), first_line + 1, first_preset.name()),
+            );
+        }
+
+        proofs.push(proof);
+    }
+
+    println!("Aggregating {} game proofs (top {})", proofs.len(), args.top_k);
+
+    // Setup prover client
+    let client = ProverClient::from_env();
+    let (_, game_vk) = setup_cached(&client, ZKLABUBU_PROOF_PROGRAM, ZKLABUBU_PROOF_ELF, args.rebuild_keys);
+
+    // Prepare inputs
+    let mut stdin = SP1Stdin::new();
+    stdin.write(&game_vk.hash_u32());
+    stdin.write(&args.top_k);
+    stdin.write(&args.ticket_issuer);
+    stdin.write(&expected_rules);
+    stdin.write(&args.allow_untracked);
+    let public_values: Vec<Vec<u8>> = proofs.iter().map(|proof| proof.public_values.to_vec()).collect();
+    stdin.write(&public_values);
+
+    // Game proofs are verified in the program with SP1 recursion
+    for proof in proofs {
+        let SP1Proof::Compressed(compressed) = proof.proof else {
+            eprintln!("Error: game proofs must be compressed (use prove-batch)");
+            std::process::exit(1);
+        };
+        stdin.write_proof(*compressed, game_vk.vk.clone());
+    }
+
+    let public_values = if args.execute {
+        // Run program without generating proof (recursive verification is skipped)
+        let (output, report) = client.execute(ZKLABUBU_AGGREGATION_ELF, &stdin).run().unwrap();
+        println!("Program executed successfully.");
+        println!("Number of instructions executed: {}", report.total_instruction_count());
+        output
+    } else {
+        let (pk, vk) = setup_cached(&client, ZKLABUBU_AGGREGATION_PROGRAM, ZKLABUBU_AGGREGATION_ELF, args.rebuild_keys);
+
+        println!("Generating leaderboard proof...");
+        let proof = client
+            .prove(&pk, &stdin)
+            .compressed()
+            .run()
+            .expect("proof generation failed");
+
+        client.verify(&proof, &vk).expect("proof verification failed");
+        println!("Leaderboard proof successfully verified!");
+        println!("Aggregation VKey: {}", vk.bytes32());
+
+        proof.save(&args.output).expect("failed to save proof");
+        println!("Proof saved to file: {}", args.output.display());
+        proof.public_values
+    };
+
+    // Read output
+    let leaderboard = LeaderboardStruct::abi_decode(public_values.as_slice(), true).unwrap();
+    println!("Game VKey: {}", leaderboard.gameVkey);
+    println!("Total games: {}", leaderboard.totalGames);
+    println!("Ticket issuer: {}", leaderboard.ticketIssuer);
+    if leaderboard.challengeDate != 0 {
+        println!("Daily challenge: {}", format_challenge_date(leaderboard.challengeDate));
+    }
+    println!("Rules hash: {}", leaderboard.rulesHash);
+    if let Some(difficulty) = Difficulty::from_code(leaderboard.difficulty) {
+        println!("Difficulty: {}", difficulty.name());
+    }
+    for (rank, entry) in leaderboard.entries.iter().enumerate() {
+        println!("#{} player {} score {} (proof {})", rank + 1, entry.player, entry.score, entry.proofDigest);
+    }
+}
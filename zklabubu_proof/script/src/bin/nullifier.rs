@@ -0,0 +1,43 @@
+use alloy_primitives::B256;
+use clap::Parser;
+use serde::Serialize;
+use zklabubu_proof_script::nullifiers::{NullifierRegistry, SpentNullifier};
+
+/// Command line arguments
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Nullifier committed by a game proof (session ticket nonce)
+    nullifier: B256,
+}
+
+/// Query result, printed as JSON.
+#[derive(Serialize, Debug)]
+struct NullifierStatus {
+    nullifier: B256,
+    spent: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submission: Option<SpentNullifier>,
+}
+
+fn main() {
+    dotenv::dotenv().ok();
+
+    let args = Args::parse();
+
+    let registry = NullifierRegistry::from_env().unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    });
+    let submission = registry.get(args.nullifier).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    });
+
+    let status = NullifierStatus {
+        nullifier: args.nullifier,
+        spent: submission.is_some(),
+        submission,
+    };
+    println!("{}", serde_json::to_string_pretty(&status).expect("failed to serialize nullifier status"));
+}
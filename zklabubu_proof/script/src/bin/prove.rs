@@ -1,10 +1,17 @@
-use alloy_sol_types::SolType;
-use clap::Parser;
-use zklabubu_proof_lib::{calculate_score, PublicValuesStruct};
-use sp1_sdk::{include_elf, ProverClient, SP1Stdin};
-
-/// RISC-V ELF file for the zkLabubuio game proof program.
-pub const ZKLABUBU_PROOF_ELF: &[u8] = include_elf!("zklabubu_proof_program");
+use alloy_primitives::{Address, Bytes, B256};
+use clap::{Parser, ValueEnum};
+use zklabubu_proof_lib::{
+    achievement_names, decode_public_values, format_challenge_date, session_digest, EggReveal,
+    Difficulty, GameConfig, GameEvent, ProofKind, PublicValues, PublicValuesV2, SignedTicket,
+};
+use zklabubu_proof_script::{
+    input::GameInput,
+    key_cache::setup_cached,
+    nullifiers::{NullifierError, NullifierRegistry, SpentNullifier, EXIT_ALREADY_SUBMITTED},
+    proof_file::{default_proof_path, ProofFile},
+    ZKLABUBU_PROOF_ELF, ZKLABUBU_PROOF_PROGRAM,
+};
+use sp1_sdk::ProverClient;
 
 /// Command line arguments
 #[derive(Parser, Debug)]
@@ -33,6 +40,176 @@ struct Args {
 
     #[clap(long, default_value = "3")]
     lives: u32,
+
+    /// Rocks that hit the player without a shield
+    #[clap(long, default_value = "0")]
+    rock_hits: u32,
+
+    /// Rocks blocked by a shield
+    #[clap(long, default_value = "0")]
+    shield_blocks: u32,
+
+    /// Extra life pickups that added a life
+    #[clap(long, default_value = "0")]
+    extra_lives: u32,
+
+    /// Player signature over the session digest (binds the proof to the player's address)
+    #[clap(long)]
+    player_signature: Option<Bytes>,
+
+    /// Session ticket issued by the game service (JSON)
+    #[clap(long, value_parser = parse_ticket)]
+    ticket: Option<SignedTicket>,
+
+    /// Address of the game service the ticket must be signed by ($TICKET_ISSUER if unset)
+    #[clap(long)]
+    ticket_issuer: Option<Address>,
+
+    /// What the proof commits
+    #[clap(long, value_enum, default_value = "full")]
+    kind: KindArg,
+
+    /// Score to reach (threshold proofs)
+    #[clap(long, required_if_eq("kind", "threshold"))]
+    threshold: Option<u32>,
+
+    /// Salt of the egg or score commitment (private and threshold proofs, random if unset)
+    #[clap(long)]
+    salt: Option<B256>,
+
+    /// Event ledger of the game (JSON file), replayed to prove achievements
+    #[clap(long)]
+    ledger: Option<std::path::PathBuf>,
+
+    /// Game balance the game was played with (JSON file, the default config if unset)
+    #[clap(long)]
+    config: Option<std::path::PathBuf>,
+
+    /// Where the secret of a private or threshold proof is written (kept out of the log)
+    #[clap(long)]
+    reveal_out: Option<std::path::PathBuf>,
+
+    /// Where the proof is saved (must not exist yet, a unique file in the working directory if unset)
+    #[clap(long)]
+    proof_out: Option<std::path::PathBuf>,
+
+    /// Difficulty preset the game was played on
+    #[clap(long, value_enum, default_value = "normal")]
+    difficulty: DifficultyArg,
+
+    /// Ignore the cached SP1 keys and run the setup again
+    #[clap(long)]
+    rebuild_keys: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum KindArg {
+    /// Commit the whole game summary
+    Full,
+    /// Commit a salted commitment instead of the egg counts
+    Private,
+    /// Commit only whether the score reached --threshold
+    Threshold,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum DifficultyArg {
+    Easy,
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl From<DifficultyArg> for Difficulty {
+    fn from(arg: DifficultyArg) -> Self {
+        match arg {
+            DifficultyArg::Easy => Difficulty::Easy,
+            DifficultyArg::Normal => Difficulty::Normal,
+            DifficultyArg::Hard => Difficulty::Hard,
+            DifficultyArg::Nightmare => Difficulty::Nightmare,
+        }
+    }
+}
+
+impl Args {
+    fn proof_kind(&self) -> ProofKind {
+        match self.kind {
+            KindArg::Full => ProofKind::Full,
+            KindArg::Private => ProofKind::Private {
+                salt: self.salt.unwrap_or_else(B256::random),
+            },
+            KindArg::Threshold => ProofKind::Threshold {
+                threshold: self.threshold.unwrap_or_default(),
+                salt: self.salt.unwrap_or_else(B256::random),
+            },
+        }
+    }
+}
+
+// Decode the committed public values (any version and kind)
+fn decode_output(public_values: &[u8]) -> PublicValues {
+    decode_public_values(public_values).unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    })
+}
+
+// The pinned game service from $TICKET_ISSUER
+fn env_ticket_issuer() -> Option<Address> {
+    let issuer = std::env::var("TICKET_ISSUER").ok()?;
+    Some(issuer.parse().unwrap_or_else(|err| {
+        eprintln!("Error: invalid TICKET_ISSUER: {}", err);
+        std::process::exit(1);
+    }))
+}
+
+// Stop on a nullifier registry error ("already submitted" has its own exit code)
+fn exit_with(err: NullifierError) -> ! {
+    eprintln!("Error: {}", err);
+    match err {
+        NullifierError::AlreadySubmitted { .. } => std::process::exit(EXIT_ALREADY_SUBMITTED),
+        NullifierError::Storage(_) => std::process::exit(1),
+    }
+}
+
+fn parse_ticket(json: &str) -> Result<SignedTicket, String> {
+    serde_json::from_str(json).map_err(|err| format!("invalid session ticket: {}", err))
+}
+
+fn read_ledger(path: &std::path::Path) -> Vec<GameEvent> {
+    let json = std::fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Error: cannot read ledger {}: {}", path.display(), err);
+        std::process::exit(1);
+    });
+    serde_json::from_str(&json).unwrap_or_else(|err| {
+        eprintln!("Error: invalid ledger: {}", err);
+        std::process::exit(1);
+    })
+}
+
+fn read_config(path: &std::path::Path) -> GameConfig {
+    let json = std::fs::read_to_string(path).unwrap_or_else(|err| {
+        eprintln!("Error: cannot read game config {}: {}", path.display(), err);
+        std::process::exit(1);
+    });
+    GameConfig::from_json(&json).unwrap_or_else(|err| {
+        eprintln!("Error: invalid game config: {}", err);
+        std::process::exit(1);
+    })
+}
+
+// Write the secret of a private or threshold proof to --reveal-out, never to the log
+fn write_reveal(path: Option<&std::path::Path>, reveal: &impl serde::Serialize) {
+    let Some(path) = path else {
+        println!("Use --reveal-out to keep the salt, the commitment can't be opened without it");
+        return;
+    };
+    let json = serde_json::to_string_pretty(reveal).expect("failed to serialize reveal");
+    std::fs::write(path, json + "\n").unwrap_or_else(|err| {
+        eprintln!("Error: cannot write reveal {}: {}", path.display(), err);
+        std::process::exit(1);
+    });
+    println!("Reveal saved to {} (keep it private, share it to open the commitment)", path.display());
 }
 
 fn main() {
@@ -52,16 +229,93 @@ fn main() {
     let client = ProverClient::from_env();
 
     // Prepare inputs
-    let mut stdin = SP1Stdin::new();
-    stdin.write(&args.yellow_eggs);
-    stdin.write(&args.blue_eggs);
-    stdin.write(&args.purple_eggs);
-    stdin.write(&args.score);
-    stdin.write(&args.game_time);
-    stdin.write(&args.lives);
+    let input = GameInput {
+        yellow_eggs: args.yellow_eggs,
+        blue_eggs: args.blue_eggs,
+        purple_eggs: args.purple_eggs,
+        score: args.score,
+        game_time: args.game_time,
+        lives: args.lives,
+        rock_hits: args.rock_hits,
+        shield_blocks: args.shield_blocks,
+        extra_lives: args.extra_lives,
+        player_signature: args.player_signature.clone(),
+        ticket: args.ticket.clone(),
+        ticket_issuer: args.ticket_issuer.or_else(env_ticket_issuer).unwrap_or_default(),
+        kind: args.proof_kind(),
+        ledger: args.ledger.as_deref().map(read_ledger),
+        config: args.config.as_deref().map(read_config).unwrap_or_default(),
+        difficulty: args.difficulty.into(),
+    };
+    let stdin = input.to_stdin();
+    let rules_config = input.rules_config();
+
+    // The preset must leave a playable config
+    if let Err(err) = rules_config.validate() {
+        eprintln!("Error: invalid game config: {} ({} difficulty)", err, input.difficulty.name());
+        std::process::exit(1);
+    }
+
+    // Only tickets of the pinned service that are still valid can be proven
+    if let Err(err) = input.check_ticket() {
+        eprintln!("Error: invalid session ticket: {}", err);
+        std::process::exit(1);
+    }
+
+    // The program would refuse to run on an impossible game
+    if let Err(err) = input.check_plausibility() {
+        eprintln!("Error: implausible game: {}", err);
+        std::process::exit(1);
+    }
+    let calculated_score = input.calculated_score().expect("score checked by check_plausibility");
+    let achievements = input.achievements().unwrap_or_else(|err| {
+        eprintln!("Error: invalid ledger: {}", err);
+        std::process::exit(1);
+    });
 
-    println!("Game Data: Yellow Eggs = {}, Blue Eggs = {}, Purple Eggs = {}, Score = {}",
-             args.yellow_eggs, args.blue_eggs, args.purple_eggs, args.score);
+    // The egg breakdown of a private proof and the score of a threshold proof stay out of the log
+    match &input.kind {
+        ProofKind::Private { .. } => println!("Game Data: Score = {} (egg counts hidden)", args.score),
+        ProofKind::Threshold { .. } => println!("Game Data: score and egg counts hidden"),
+        _ => println!("Game Data: Yellow Eggs = {}, Blue Eggs = {}, Purple Eggs = {}, Score = {}",
+                      args.yellow_eggs, args.blue_eggs, args.purple_eggs, args.score),
+    }
+    println!("Session digest (sign to claim): {}", session_digest(&input.session()));
+    println!("Difficulty: {}", input.difficulty.name());
+    println!("Rules hash of the game config: {}", rules_config.rules_hash());
+    // Threshold proofs don't commit the achievements
+    if input.ledger.is_some() && !matches!(input.kind, ProofKind::Threshold { .. }) {
+        let names = achievement_names(achievements);
+        println!("Achievements: {}", names.join(", "));
+        // Machine-readable copy for the backend
+        println!("Achievements JSON: {}", serde_json::to_string(&names).expect("failed to serialize achievements"));
+    }
+    match &input.kind {
+        ProofKind::Full => {}
+        ProofKind::Private { salt } => {
+            // Disclosing this reveals the egg breakdown of the proof
+            let reveal = EggReveal {
+                salt: *salt,
+                yellow_eggs: args.yellow_eggs,
+                pink_eggs: args.blue_eggs,
+                purple_eggs: args.purple_eggs,
+            };
+            println!("Private proof: egg counts are committed, not published");
+            write_reveal(args.reveal_out.as_deref(), &reveal);
+        }
+        ProofKind::Threshold { threshold, salt } => {
+            // Disclosing this opens the score commitment
+            let opening = serde_json::json!({
+                "salt": salt,
+                "score": calculated_score,
+                "yellowEggs": args.yellow_eggs,
+                "pinkEggs": args.blue_eggs,
+                "purpleEggs": args.purple_eggs,
+            });
+            println!("Threshold proof: score >= {}", threshold);
+            write_reveal(args.reveal_out.as_deref(), &opening);
+        }
+    }
 
     if args.execute {
         // Run program without generating proof
@@ -69,28 +323,87 @@ fn main() {
         println!("Program executed successfully.");
 
         // Read output
-        let decoded = PublicValuesStruct::abi_decode(output.as_slice(), true).unwrap();
-        let PublicValuesStruct { score, yellowEggs, pinkEggs, purpleEggs, gameTime, lives } = decoded;
-        
-        // Compare calculated score with reported score
-        let expected_score = calculate_score(args.yellow_eggs, args.blue_eggs, args.purple_eggs);
-        let is_valid = expected_score == args.score;
-        
-        println!("Calculated Score: {}", score);
-        println!("Reported Score: {}", args.score);
-        println!("Score Verification: {}", if is_valid { "SUCCESS" } else { "FAILED" });
-        println!("Yellow Eggs: {}", yellowEggs);
-        println!("Pink Eggs: {}", pinkEggs);
-        println!("Purple Eggs: {}", purpleEggs);
-        println!("Game Time: {}s", gameTime);
-        println!("Lives: {}", lives);
+        let decoded = decode_output(output.as_slice());
+        println!("Public values: version {}, {} proof", decoded.version(), decoded.kind_name());
+        if let Some(rules_hash) = decoded.rules_hash() {
+            println!("Rules Hash: {}", rules_hash);
+        }
+        if let Some(difficulty) = decoded.difficulty() {
+            println!("Difficulty: {}", difficulty.name());
+        }
+        if let Some(date) = decoded.challenge_date() {
+            println!("Daily Challenge: {}", format_challenge_date(date));
+        }
+
+        match &decoded {
+            PublicValues::V1(_) => println!("Legacy public values (no version header)"),
+            PublicValues::Full(values) => {
+                let PublicValuesV2 {
+                    score, yellowEggs, pinkEggs, purpleEggs, gameTime, lives,
+                    rockHits, shieldBlocks, extraLives, achievements,
+                    player, nullifier, seed, ticketExpiresAt, ticketIssuer, ..
+                } = values;
+
+                // Compare calculated score with reported score
+                let is_valid = calculated_score == args.score;
+
+                println!("Calculated Score: {}", score);
+                println!("Reported Score: {}", args.score);
+                println!("Score Verification: {}", if is_valid { "SUCCESS" } else { "FAILED" });
+                println!("Yellow Eggs: {}", yellowEggs);
+                println!("Pink Eggs: {}", pinkEggs);
+                println!("Purple Eggs: {}", purpleEggs);
+                println!("Game Time: {}s", gameTime);
+                println!("Lives: {}", lives);
+                println!("Rock Hits: {}, Shield Blocks: {}, Extra Lives: {}", rockHits, shieldBlocks, extraLives);
+                println!("Achievements: {}", achievement_names(*achievements).join(", "));
+                println!("Player: {}", player);
+                println!("Ticket: issuer {}, seed {}, expires at {}", ticketIssuer, seed, ticketExpiresAt);
+                println!("Nullifier: {}", nullifier);
+            }
+            PublicValues::Private(values) => {
+                println!("Calculated Score: {}", values.score);
+                println!("Eggs Commitment: {}", values.eggsCommitment);
+                println!("Game Time: {}s", values.gameTime);
+                println!("Lives: {}", values.lives);
+                println!("Achievements: {}", achievement_names(values.achievements).join(", "));
+                println!("Player: {}", values.player);
+                println!("Nullifier: {}", values.nullifier);
+            }
+            PublicValues::Threshold(values) => {
+                println!("Threshold: {}", values.threshold);
+                println!("Threshold Verification: {}", if values.passed { "PASSED" } else { "NOT PASSED" });
+                println!("Score Commitment: {}", values.scoreCommitment);
+                println!("Player: {}", values.player);
+                println!("Ticket issuer: {}", values.ticketIssuer);
+                println!("Nullifier: {}", values.nullifier);
+            }
+        }
+        let nullifier = decoded.nullifier();
+
+        // Proving would be refused if the game was already submitted
+        if let Err(err) = NullifierRegistry::from_env().and_then(|registry| registry.check(nullifier)) {
+            println!("Warning: {}", err);
+        }
 
         // Log executed instruction count
         println!("Number of instructions executed: {}", report.total_instruction_count());
     } else {
-        // Setup program for proof generation
-        println!("Setting up SP1 program...");
-        let (pk, vk) = client.setup(ZKLABUBU_PROOF_ELF);
+        // Reject a game that was already submitted before the slow proving
+        let registry = NullifierRegistry::from_env().unwrap_or_else(|err| exit_with(err));
+        if let Some(ticket) = &input.ticket {
+            registry.check(ticket.nonce).unwrap_or_else(|err| exit_with(err));
+        }
+
+        // Claim the proof file, so a concurrent run can't overwrite it
+        let proof_path = args.proof_out.clone().unwrap_or_else(default_proof_path);
+        let proof_file = ProofFile::reserve(&proof_path).unwrap_or_else(|err| {
+            eprintln!("Error: cannot create proof file {}: {}", proof_path.display(), err);
+            std::process::exit(1);
+        });
+
+        // Setup program for proof generation (keys are cached on disk)
+        let (pk, vk) = setup_cached(&client, ZKLABUBU_PROOF_PROGRAM, ZKLABUBU_PROOF_ELF, args.rebuild_keys);
         
         // Generate standard proof for local development
         println!("Generating standard proof...");
@@ -107,9 +420,23 @@ fn main() {
         println!("Standard proof successfully verified!");
         
         // Save proof to disk
-        let proof_path = "zklabubuio_game_proof.bin";
-        proof.save(proof_path).expect("failed to save proof");
-        println!("Proof saved to file: {}", proof_path);
+        proof.save(proof_file.path()).expect("failed to save proof");
+        println!("Proof saved to file: {}", proof_file.path().display());
+
+        // Mark the game as submitted
+        let decoded = decode_output(proof.public_values.as_slice());
+        let (player, nullifier) = (decoded.player(), decoded.nullifier());
+        let spent = SpentNullifier::new(player, calculated_score, proof_file.path().display().to_string());
+        if let Err(err) = registry.spend(nullifier, &spent) {
+            // Another run was first: remove this run's proof (never the proof the other run recorded)
+            drop(proof_file);
+            exit_with(err);
+        }
+        proof_file.keep();
+        if !nullifier.is_zero() {
+            println!("Nullifier spent: {}", nullifier);
+        }
+        println!("Proof kind: {}", input.kind.name());
         
         // Print proof size information
         println!("Proof type: Standard (SP1Receipt)");
@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use alloy_primitives::{Address, B256};
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::ProverClient;
+use zklabubu_proof_lib::{decode_public_values, ProofKind};
+use zklabubu_proof_script::{
+    input::GameInput,
+    key_cache::setup_cached,
+    nullifiers::{NullifierError, NullifierRegistry, SpentNullifier},
+    proof_file::ProofFile,
+    ZKLABUBU_PROOF_ELF, ZKLABUBU_PROOF_PROGRAM,
+};
+
+/// Command line arguments
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// JSONL file with one game per line: {"id": "...", "yellow_eggs": 5, ...}
+    #[clap(long)]
+    input: PathBuf,
+
+    /// Directory receiving one proof per game and the summary report
+    #[clap(long, default_value = "batch-proofs")]
+    out_dir: PathBuf,
+
+    /// Only execute the games, don't generate proofs
+    #[clap(long)]
+    execute_only: bool,
+
+    /// Ignore the cached SP1 keys and run the setup again
+    #[clap(long)]
+    rebuild_keys: bool,
+
+    /// Address of the game service every ticket must be signed by (overrides `ticket_issuer` of the lines)
+    #[clap(long)]
+    ticket_issuer: Option<Address>,
+}
+
+/// One line of the batch file.
+#[derive(Deserialize, Debug)]
+struct BatchLine {
+    id: String,
+    #[serde(flatten)]
+    game: GameInput,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum BatchStatus {
+    Invalid,
+    Executed,
+    Proved,
+    Failed,
+}
+
+/// Result for one game, written to the summary report.
+#[derive(Serialize, Debug)]
+struct BatchEntry {
+    /// Line of the game in the input file (1-based)
+    line: usize,
+    id: String,
+    status: BatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    score: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cycles: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proof: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    nullifier: Option<B256>,
+}
+
+impl BatchEntry {
+    fn new(line: usize, id: impl Into<String>, status: BatchStatus) -> Self {
+        BatchEntry {
+            line,
+            id: id.into(),
+            status,
+            reason: None,
+            score: None,
+            cycles: None,
+            proof: None,
+            nullifier: None,
+        }
+    }
+
+    fn invalid(line: usize, id: impl Into<String>, reason: impl Into<String>) -> Self {
+        BatchEntry { reason: Some(reason.into()), ..Self::new(line, id, BatchStatus::Invalid) }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct BatchSummary {
+    total: usize,
+    invalid: usize,
+    proved: usize,
+    failed: usize,
+    entries: Vec<BatchEntry>,
+}
+
+// Line ids become file names, so keep them simple
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !id.starts_with('.')
+}
+
+fn main() {
+    // Setup logger
+    sp1_sdk::utils::setup_logger();
+    dotenv::dotenv().ok();
+
+    let args = Args::parse();
+
+    let contents = fs::read_to_string(&args.input).unwrap_or_else(|err| {
+        eprintln!("Error: could not read {}: {}", args.input.display(), err);
+        std::process::exit(1);
+    });
+    fs::create_dir_all(&args.out_dir).expect("failed to create output directory");
+
+    let registry = NullifierRegistry::from_env().unwrap_or_else(|err| {
+        eprintln!("Error: {}", err);
+        std::process::exit(1);
+    });
+
+    // Parse all lines first, so a bad file fails before the slow setup
+    let mut entries = Vec::new();
+    let mut games = Vec::new();
+    let mut seen_ids = HashSet::new();
+    for (line_number, line) in contents.lines().enumerate() {
+        let line_number = line_number + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let batch_line = match serde_json::from_str::<BatchLine>(line) {
+            Ok(batch_line) => batch_line,
+            Err(err) => {
+                let reason = format!("invalid JSON: {}", err);
+                entries.push(BatchEntry::invalid(line_number, format!("line-{}", line_number), reason));
+                continue;
+            }
+        };
+
+        if !is_valid_id(&batch_line.id) {
+            entries.push(BatchEntry::invalid(
+                line_number,
+                batch_line.id,
+                "id may only contain letters, digits, '-', '_' and '.'",
+            ));
+        } else if !seen_ids.insert(batch_line.id.clone()) {
+            entries.push(BatchEntry::invalid(line_number, batch_line.id, "duplicate id"));
+        } else {
+            games.push((line_number, batch_line));
+        }
+    }
+
+    println!("Loaded {} games ({} rejected while parsing)", games.len(), entries.len());
+
+    // Setup prover client once for the whole batch, and the keys only if the games get proven
+    let client = ProverClient::from_env();
+    let keys = if args.execute_only {
+        None
+    } else {
+        Some(setup_cached(&client, ZKLABUBU_PROOF_PROGRAM, ZKLABUBU_PROOF_ELF, args.rebuild_keys))
+    };
+
+    // Execute everything first to filter out invalid games
+    let mut valid = Vec::new();
+    let mut batch_nullifiers = HashSet::new();
+    for (line, BatchLine { id, mut game }) in games {
+        if let Some(issuer) = args.ticket_issuer {
+            game.ticket_issuer = issuer;
+        }
+
+        // Batches feed the leaderboard, which needs the full summary
+        if game.kind != ProofKind::Full {
+            entries.push(BatchEntry::invalid(line, id, format!("{} proofs can't be batched", game.kind.name())));
+            continue;
+        }
+
+        if let Err(err) = game.config.validate().and_then(|()| game.rules_config().validate()) {
+            println!("[{}] invalid game config: {}", id, err);
+            entries.push(BatchEntry::invalid(line, id, format!("invalid game config: {}", err)));
+            continue;
+        }
+        if let Err(err) = game.check_ticket() {
+            println!("[{}] invalid session ticket: {}", id, err);
+            entries.push(BatchEntry::invalid(line, id, format!("invalid session ticket: {}", err)));
+            continue;
+        }
+        if let Err(err) = game.check_plausibility() {
+            println!("[{}] implausible game: {}", id, err);
+            entries.push(BatchEntry::invalid(line, id, format!("implausible game: {}", err)));
+            continue;
+        }
+        if let Err(err) = game.achievements() {
+            println!("[{}] invalid ledger: {}", id, err);
+            entries.push(BatchEntry::invalid(line, id, format!("invalid ledger: {}", err)));
+            continue;
+        }
+
+        let (output, report) = match client.execute(ZKLABUBU_PROOF_ELF, &game.to_stdin()).run() {
+            Ok(result) => result,
+            Err(err) => {
+                println!("[{}] execution failed: {}", id, err);
+                entries.push(BatchEntry::invalid(line, id, format!("execution failed: {}", err)));
+                continue;
+            }
+        };
+
+        let decoded = match decode_public_values(output.as_slice()) {
+            Ok(decoded) => decoded,
+            Err(err) => {
+                println!("[{}] {}", id, err);
+                entries.push(BatchEntry::invalid(line, id, err.to_string()));
+                continue;
+            }
+        };
+        // Batches only hold full proofs, so the score is public
+        let score = decoded.score().unwrap_or_default();
+        let nullifier = decoded.nullifier();
+        if score != game.score {
+            println!("[{}] score mismatch: reported {}, calculated {}", id, game.score, score);
+            let reason = format!("score mismatch: reported {}, calculated {}", game.score, score);
+            entries.push(BatchEntry::invalid(line, id, reason));
+            continue;
+        }
+
+        // Each game can only be submitted once
+        match registry.check(nullifier) {
+            Ok(()) => {}
+            Err(err @ NullifierError::AlreadySubmitted { .. }) => {
+                println!("[{}] {}", id, err);
+                entries.push(BatchEntry::invalid(line, id, err.to_string()));
+                continue;
+            }
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1);
+            }
+        }
+        if !nullifier.is_zero() && !batch_nullifiers.insert(nullifier) {
+            println!("[{}] duplicate nullifier {}", id, nullifier);
+            let reason = format!("game already in batch: duplicate nullifier {}", nullifier);
+            entries.push(BatchEntry::invalid(line, id, reason));
+            continue;
+        }
+
+        println!("[{}] executed, score {}", id, score);
+        let mut entry = BatchEntry::new(line, id, BatchStatus::Executed);
+        entry.score = Some(score);
+        entry.cycles = Some(report.total_instruction_count());
+        entry.nullifier = Some(nullifier).filter(|nullifier| !nullifier.is_zero());
+        valid.push((entry, game, decoded.player()));
+    }
+
+    println!("{} valid games", valid.len());
+
+    // Prove the valid games (compressed, so they can be aggregated)
+    for (mut entry, game, player) in valid {
+        let Some((pk, vk)) = &keys else {
+            entries.push(entry);
+            continue;
+        };
+
+        // Claim the proof file, so a concurrent batch writing to the same directory can't overwrite it
+        let proof_path = args.out_dir.join(format!("{}.bin", entry.id));
+        let proof_file = match ProofFile::reserve(&proof_path) {
+            Ok(proof_file) => proof_file,
+            Err(err) => {
+                println!("[{}] cannot create proof file {}: {}", entry.id, proof_path.display(), err);
+                entry.status = BatchStatus::Failed;
+                entry.reason = Some(format!("cannot create proof file {}: {}", proof_path.display(), err));
+                entries.push(entry);
+                continue;
+            }
+        };
+
+        println!("[{}] generating compressed proof...", entry.id);
+        let result = client
+            .prove(pk, &game.to_stdin())
+            .compressed()
+            .run()
+            .and_then(|proof| client.verify(&proof, vk).map(|_| proof).map_err(Into::into));
+
+        match result {
+            Ok(proof) => {
+                match proof.save(proof_file.path()) {
+                    Ok(()) => {
+                        println!("[{}] proof saved to {}", entry.id, proof_path.display());
+
+                        // Mark the game as submitted
+                        let score = entry.score.unwrap_or_default();
+                        let spent = SpentNullifier::new(player, score, proof_path.display().to_string());
+                        match registry.spend(entry.nullifier.unwrap_or_default(), &spent) {
+                            Ok(()) => {
+                                entry.status = BatchStatus::Proved;
+                                entry.proof = Some(proof_file.keep());
+                            }
+                            // The proof file of this run is removed on drop
+                            Err(err) => {
+                                println!("[{}] {}", entry.id, err);
+                                entry.status = match err {
+                                    NullifierError::AlreadySubmitted { .. } => BatchStatus::Invalid,
+                                    NullifierError::Storage(_) => BatchStatus::Failed,
+                                };
+                                entry.reason = Some(err.to_string());
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        entry.status = BatchStatus::Failed;
+                        entry.reason = Some(format!("could not save proof: {}", err));
+                    }
+                }
+            }
+            Err(err) => {
+                println!("[{}] proof generation failed: {}", entry.id, err);
+                entry.status = BatchStatus::Failed;
+                entry.reason = Some(format!("proof generation failed: {}", err));
+            }
+        }
+        entries.push(entry);
+    }
+
+    // Write the summary report, in the order of the input file
+    entries.sort_by_key(|entry| entry.line);
+    let count = |status| entries.iter().filter(|entry| entry.status == status).count();
+    let summary = BatchSummary {
+        total: entries.len(),
+        invalid: count(BatchStatus::Invalid),
+        proved: count(BatchStatus::Proved),
+        failed: count(BatchStatus::Failed),
+        entries,
+    };
+
+    let summary_path = args.out_dir.join("summary.json");
+    let json = serde_json::to_string_pretty(&summary).expect("failed to serialize summary");
+    fs::write(&summary_path, json).expect("failed to write summary");
+
+    println!(
+        "Batch done: {} games, {} proved, {} invalid, {} failed",
+        summary.total, summary.proved, summary.invalid, summary.failed
+    );
+    println!("Summary saved to: {}", summary_path.display());
+
+    if summary.failed > 0 {
+        std::process::exit(1);
+    }
+}
@@ -1,15 +1,115 @@
-use sp1_sdk::{include_elf, HashableKey, ProverClient};
+use std::path::{Path, PathBuf};
 
-/// RISC-V ELF file for the zkLabubuio game proof program.
-pub const ZKLABUBU_PROOF_ELF: &[u8] = include_elf!("zklabubu_proof_program");
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{HashableKey, ProverClient, SP1_CIRCUIT_VERSION};
+use zklabubu_proof_script::{
+    key_cache::{elf_hash, setup_cached},
+    ZKLABUBU_PROOF_ELF, ZKLABUBU_PROOF_PROGRAM,
+};
+
+/// Command line arguments
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Args {
+    /// Ignore the cached SP1 keys and run the setup again
+    #[clap(long)]
+    rebuild_keys: bool,
+
+    /// Path of the committed verification key manifest
+    #[clap(long, default_value = "vkey.json")]
+    manifest: PathBuf,
+
+    /// Write the current verification key to the manifest instead of checking it
+    #[clap(long)]
+    update: bool,
+}
+
+/// Verification key of the deployed program, committed next to the script.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct VkeyManifest {
+    vkey: String,
+    elf_sha256: String,
+    sp1_version: String,
+}
+
+impl VkeyManifest {
+    /// Fields that differ from `current`, as (name, committed, current)
+    fn diff<'a>(&'a self, current: &'a VkeyManifest) -> Vec<(&'static str, &'a str, &'a str)> {
+        [
+            ("vkey", &self.vkey, &current.vkey),
+            ("elf_sha256", &self.elf_sha256, &current.elf_sha256),
+            ("sp1_version", &self.sp1_version, &current.sp1_version),
+        ]
+        .into_iter()
+        .filter(|(_, committed, current)| committed != current)
+        .map(|(name, committed, current)| (name, committed.as_str(), current.as_str()))
+        .collect()
+    }
+}
+
+/// Write `manifest` as pretty JSON to `path`
+fn write_manifest(path: &Path, manifest: &VkeyManifest) {
+    let json = serde_json::to_string_pretty(manifest).expect("failed to serialize vkey manifest");
+    std::fs::write(path, json + "\n").expect("failed to write vkey manifest");
+}
 
 fn main() {
+    let args = Args::parse();
+
     // Setup prover client
     let client = ProverClient::from_env();
-    
-    // Get verification key for the program
-    let (_, vk) = client.setup(ZKLABUBU_PROOF_ELF);
-    
+
+    // Get verification key for the program (keys are cached on disk)
+    let (_, vk) = setup_cached(&client, ZKLABUBU_PROOF_PROGRAM, ZKLABUBU_PROOF_ELF, args.rebuild_keys);
+
     // Print verification key
     println!("Program VKey: {}", vk.bytes32());
-}
\ No newline at end of file
+
+    let current = VkeyManifest {
+        vkey: vk.bytes32(),
+        elf_sha256: elf_hash(ZKLABUBU_PROOF_ELF),
+        sp1_version: SP1_CIRCUIT_VERSION.to_string(),
+    };
+
+    if args.update {
+        write_manifest(&args.manifest, &current);
+        println!("Verification key written to {}", args.manifest.display());
+        return;
+    }
+
+    // Compare against the committed manifest
+    let committed: VkeyManifest = match std::fs::read_to_string(&args.manifest) {
+        Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+            eprintln!("Error: {} is not a valid vkey manifest: {}", args.manifest.display(), err);
+            std::process::exit(1);
+        }),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            // First run: record the current key so later runs have something to check against
+            write_manifest(&args.manifest, &current);
+            println!("No vkey manifest found, created {}", args.manifest.display());
+            println!("Commit it together with the verifier deployment that uses this key.");
+            return;
+        }
+        Err(err) => {
+            eprintln!("Error: could not read {}: {}", args.manifest.display(), err);
+            std::process::exit(1);
+        }
+    };
+
+    let diff = committed.diff(&current);
+    if diff.is_empty() {
+        println!("Verification key matches {}", args.manifest.display());
+        return;
+    }
+
+    eprintln!("Error: the program verification key drifted from {}", args.manifest.display());
+    for (field, committed, current) in &diff {
+        eprintln!("  {}:", field);
+        eprintln!("    - committed: {}", committed);
+        eprintln!("    + current:   {}", current);
+    }
+    eprintln!("Proofs of the current program will not verify against the deployed key.");
+    eprintln!("Update the verifier, then run `cargo run --release --bin vkey -- --update` and commit the file.");
+    std::process::exit(1);
+}
@@ -0,0 +1,158 @@
+//! Game data passed to the zkVM program.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy_primitives::{Address, Bytes, B256};
+use serde::{Deserialize, Serialize};
+use sp1_sdk::SP1Stdin;
+use zklabubu_proof_lib::{
+    calculate_score, check_lives, check_plausibility, Difficulty, GameConfig, GameEvent, GameSession, Implausible, LedgerCounts, LedgerError,
+    LedgerReplay, ProofKind, SignedTicket,
+};
+
+/// One game session, as reported by the game.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct GameInput {
+    #[serde(default)]
+    pub yellow_eggs: u32,
+    #[serde(default)]
+    pub blue_eggs: u32,
+    #[serde(default)]
+    pub purple_eggs: u32,
+    #[serde(default)]
+    pub score: u32,
+    #[serde(default)]
+    pub game_time: u32,
+    #[serde(default = "default_lives")]
+    pub lives: u32,
+    /// Rocks that hit the player without a shield
+    #[serde(default)]
+    pub rock_hits: u32,
+    /// Rocks blocked by a shield
+    #[serde(default)]
+    pub shield_blocks: u32,
+    /// Extra life pickups that added a life
+    #[serde(default)]
+    pub extra_lives: u32,
+    /// Player signature over the session digest (65 bytes, hex encoded)
+    #[serde(default)]
+    pub player_signature: Option<Bytes>,
+    /// Session ticket issued by the game service
+    #[serde(default)]
+    pub ticket: Option<SignedTicket>,
+    /// Address of the game service the ticket must be signed by
+    #[serde(default)]
+    pub ticket_issuer: Address,
+    /// What the proof commits (full summary by default)
+    #[serde(default)]
+    pub kind: ProofKind,
+    /// Event ledger of the game, replayed to prove achievements
+    #[serde(default)]
+    pub ledger: Option<Vec<GameEvent>>,
+    /// Balance the game was played with (the default config if unset)
+    #[serde(default)]
+    pub config: GameConfig,
+    /// Difficulty preset applied to the config (normal if unset)
+    #[serde(default)]
+    pub difficulty: Difficulty,
+}
+
+fn default_lives() -> u32 {
+    3
+}
+
+impl GameInput {
+    /// Score of the egg counts (`None` if it overflows, which `check_plausibility` rejects).
+    pub fn calculated_score(&self) -> Option<u32> {
+        calculate_score(self.yellow_eggs, self.blue_eggs, self.purple_eggs)
+    }
+
+    /// Session the player signs to claim the proof (of a game that passed `check_plausibility`).
+    pub fn session(&self) -> GameSession {
+        GameSession {
+            score: self.calculated_score().unwrap_or_default(),
+            yellowEggs: self.yellow_eggs,
+            pinkEggs: self.blue_eggs,
+            purpleEggs: self.purple_eggs,
+            gameTime: self.game_time,
+            lives: self.lives,
+            rockHits: self.rock_hits,
+            shieldBlocks: self.shield_blocks,
+            extraLives: self.extra_lives,
+            nonce: self.ticket.as_ref().map_or(B256::ZERO, |ticket| ticket.nonce),
+        }
+    }
+
+    /// The config the game was played under: the config adjusted by the difficulty preset.
+    pub fn rules_config(&self) -> GameConfig {
+        self.difficulty.apply(&self.config)
+    }
+
+    /// The check the program makes before anything else, so bad games fail early.
+    pub fn check_plausibility(&self) -> Result<(), Implausible> {
+        let config = self.rules_config();
+        check_plausibility(&config, self.yellow_eggs, self.blue_eggs, self.purple_eggs, self.game_time, self.lives)?;
+        check_lives(
+            &config,
+            self.calculated_score().ok_or(Implausible::ScoreOverflow)?,
+            self.game_time,
+            self.lives,
+            self.rock_hits,
+            self.shield_blocks,
+            self.extra_lives,
+        )
+    }
+
+    /// The ticket checks the program makes, plus the expiry (the program has no clock).
+    pub fn check_ticket(&self) -> Result<(), String> {
+        let Some(ticket) = &self.ticket else {
+            return Ok(());
+        };
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+        ticket.verify_issuer(self.ticket_issuer).map_err(|err| err.to_string())?;
+        if ticket.is_expired(now) {
+            return Err(format!("session ticket expired at {}", ticket.expires_at));
+        }
+        Ok(())
+    }
+
+    /// Replays the ledger like the program does and returns the unlocked achievements.
+    pub fn achievements(&self) -> Result<u32, LedgerError> {
+        let Some(events) = &self.ledger else {
+            return Ok(0);
+        };
+        let replay = LedgerReplay::replay(events)?;
+        let summary = LedgerCounts {
+            yellow_eggs: self.yellow_eggs,
+            pink_eggs: self.blue_eggs,
+            purple_eggs: self.purple_eggs,
+            rock_hits: self.rock_hits,
+            shield_blocks: self.shield_blocks,
+            extra_lives: self.extra_lives,
+        };
+        replay.check_summary(&summary, self.game_time)?;
+        Ok(replay.unlocked())
+    }
+
+    /// Program inputs, in the order the program reads them.
+    pub fn to_stdin(&self) -> SP1Stdin {
+        let mut stdin = SP1Stdin::new();
+        stdin.write(&self.yellow_eggs);
+        stdin.write(&self.blue_eggs);
+        stdin.write(&self.purple_eggs);
+        stdin.write(&self.score);
+        stdin.write(&self.game_time);
+        stdin.write(&self.lives);
+        stdin.write(&self.rock_hits);
+        stdin.write(&self.shield_blocks);
+        stdin.write(&self.extra_lives);
+        stdin.write(&self.player_signature.as_ref().map(|signature| signature.to_vec()));
+        stdin.write(&self.ticket);
+        stdin.write(&self.ticket_issuer);
+        stdin.write(&self.kind);
+        stdin.write(&self.ledger);
+        stdin.write(&self.config);
+        stdin.write(&self.difficulty);
+        stdin
+    }
+}
@@ -0,0 +1,150 @@
+//! On-disk cache for the SP1 proving and verifying keys.
+//!
+//! `client.setup` is slow, so the keys are serialised to a cache directory and
+//! reused on later runs. Entries are keyed by program name and the SHA-256 of
+//! the program ELF: when a program changes its hash changes too, and its old
+//! entries are reported as stale and removed.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+use sp1_sdk::{EnvProver, SP1ProvingKey, SP1VerifyingKey, SP1_CIRCUIT_VERSION};
+
+/// Environment variable overriding the cache directory.
+pub const KEY_CACHE_DIR_ENV: &str = "ZKLABUBU_KEY_CACHE_DIR";
+
+/// Default cache directory (relative to the working directory).
+pub const DEFAULT_KEY_CACHE_DIR: &str = ".sp1-keys";
+
+const PROVING_KEY_FILE: &str = "pk.bin";
+const VERIFYING_KEY_FILE: &str = "vk.bin";
+const VERSION_FILE: &str = "sp1-version";
+
+/// SHA-256 of the program ELF, hex encoded.
+pub fn elf_hash(elf: &[u8]) -> String {
+    hex::encode(Sha256::digest(elf))
+}
+
+/// Directory holding the cached keys, as `<program>/<elf hash>/`.
+pub struct KeyCache {
+    dir: PathBuf,
+}
+
+impl KeyCache {
+    /// Cache at `$ZKLABUBU_KEY_CACHE_DIR`, or `.sp1-keys` if unset.
+    pub fn from_env() -> Self {
+        let dir = std::env::var(KEY_CACHE_DIR_ENV).unwrap_or_else(|_| DEFAULT_KEY_CACHE_DIR.to_string());
+        Self::new(dir)
+    }
+
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        KeyCache { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn program_dir(&self, program: &str) -> PathBuf {
+        self.dir.join(program)
+    }
+
+    fn entry_dir(&self, program: &str, elf: &[u8]) -> PathBuf {
+        self.program_dir(program).join(elf_hash(elf))
+    }
+
+    /// Load the keys for `elf`, if they were cached with the current SP1 version.
+    pub fn load(&self, program: &str, elf: &[u8]) -> Option<(SP1ProvingKey, SP1VerifyingKey)> {
+        let entry = self.entry_dir(program, elf);
+
+        let version = fs::read_to_string(entry.join(VERSION_FILE)).ok()?;
+        if version.trim() != SP1_CIRCUIT_VERSION {
+            println!("Cached keys were built with SP1 {}, current is {}", version.trim(), SP1_CIRCUIT_VERSION);
+            return None;
+        }
+
+        let pk = bincode::deserialize(&fs::read(entry.join(PROVING_KEY_FILE)).ok()?).ok()?;
+        let vk = bincode::deserialize(&fs::read(entry.join(VERIFYING_KEY_FILE)).ok()?).ok()?;
+        Some((pk, vk))
+    }
+
+    /// Store the keys for `elf`.
+    pub fn store(&self, program: &str, elf: &[u8], pk: &SP1ProvingKey, vk: &SP1VerifyingKey) -> io::Result<()> {
+        let entry = self.entry_dir(program, elf);
+        fs::create_dir_all(&entry)?;
+
+        let pk_bytes = bincode::serialize(pk).map_err(io::Error::other)?;
+        let vk_bytes = bincode::serialize(vk).map_err(io::Error::other)?;
+        fs::write(entry.join(PROVING_KEY_FILE), pk_bytes)?;
+        fs::write(entry.join(VERIFYING_KEY_FILE), vk_bytes)?;
+        // Written last, so a partially written entry is never loaded
+        fs::write(entry.join(VERSION_FILE), SP1_CIRCUIT_VERSION)?;
+        Ok(())
+    }
+
+    /// Cache entries of `program` built for another ELF (the program changed since).
+    pub fn stale_entries(&self, program: &str, elf: &[u8]) -> Vec<PathBuf> {
+        let current = elf_hash(elf);
+        let Ok(entries) = fs::read_dir(self.program_dir(program)) else {
+            return vec![];
+        };
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir() && entry.file_name().to_string_lossy() != current)
+            .map(|entry| entry.path())
+            .collect()
+    }
+
+    /// Remove the cached keys for `elf`.
+    pub fn remove(&self, program: &str, elf: &[u8]) -> io::Result<()> {
+        let entry = self.entry_dir(program, elf);
+        if entry.exists() {
+            fs::remove_dir_all(entry)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `client.setup(elf)`, reusing the cached keys of `program` when possible.
+///
+/// With `rebuild` set the cache is ignored and the keys are regenerated.
+pub fn setup_cached(
+    client: &EnvProver,
+    program: &str,
+    elf: &[u8],
+    rebuild: bool,
+) -> (SP1ProvingKey, SP1VerifyingKey) {
+    let cache = KeyCache::from_env();
+    println!("Setting up SP1 program...");
+
+    // The program changed: the old keys can never be used again
+    for stale in cache.stale_entries(program, elf) {
+        println!("Removing stale SP1 keys: {}", stale.display());
+        if let Err(err) = fs::remove_dir_all(&stale) {
+            eprintln!("Warning: could not remove {}: {}", stale.display(), err);
+        }
+    }
+
+    if rebuild {
+        println!("Rebuilding SP1 keys (--rebuild-keys)");
+        if let Err(err) = cache.remove(program, elf) {
+            eprintln!("Warning: could not clear cached keys: {}", err);
+        }
+    } else if let Some(keys) = cache.load(program, elf) {
+        println!("Using cached SP1 keys from {}", cache.dir().display());
+        return keys;
+    }
+
+    println!("Running the SP1 setup...");
+    let (pk, vk) = client.setup(elf);
+
+    match cache.store(program, elf, &pk, &vk) {
+        Ok(()) => println!("SP1 keys cached in {}", cache.dir().display()),
+        Err(err) => eprintln!("Warning: could not cache SP1 keys: {}", err),
+    }
+
+    (pk, vk)
+}
@@ -0,0 +1,20 @@
+//! Shared code for the zkLabubuio proof scripts.
+
+pub mod input;
+pub mod key_cache;
+pub mod nullifiers;
+pub mod proof_file;
+
+use sp1_sdk::include_elf;
+
+/// Name of the zkLabubuio game proof program.
+pub const ZKLABUBU_PROOF_PROGRAM: &str = "zklabubu_proof_program";
+
+/// RISC-V ELF file for the zkLabubuio game proof program.
+pub const ZKLABUBU_PROOF_ELF: &[u8] = include_elf!("zklabubu_proof_program");
+
+/// Name of the tournament aggregation program.
+pub const ZKLABUBU_AGGREGATION_PROGRAM: &str = "zklabubu_aggregation_program";
+
+/// RISC-V ELF file for the tournament aggregation program.
+pub const ZKLABUBU_AGGREGATION_ELF: &[u8] = include_elf!("zklabubu_aggregation_program");
@@ -0,0 +1,194 @@
+//! Persisted registry of spent nullifiers.
+//!
+//! A ticketed game commits its session nonce as a nullifier. When a proof is
+//! accepted its nullifier is recorded here, and any later proof for the same
+//! game is rejected as already submitted. Games without a ticket have the zero
+//! nullifier and cannot be deduplicated.
+//!
+//! The registry is a SQLite database, so the backend can query it while a
+//! prover is writing to it.
+
+use std::fmt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use alloy_primitives::{Address, B256};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// Environment variable overriding the registry path.
+pub const NULLIFIER_DB_ENV: &str = "ZKLABUBU_NULLIFIER_DB";
+
+/// Default registry path (relative to the working directory).
+pub const DEFAULT_NULLIFIER_DB: &str = "nullifiers.db";
+
+/// Exit code of the binaries when a game was already submitted.
+pub const EXIT_ALREADY_SUBMITTED: i32 = 3;
+
+/// The proof that spent a nullifier.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SpentNullifier {
+    pub player: Address,
+    pub score: u32,
+    /// Where the proof was saved
+    pub proof: String,
+    /// Unix time (seconds) the proof was accepted
+    pub spent_at: u64,
+}
+
+impl SpentNullifier {
+    pub fn new(player: Address, score: u32, proof: impl Into<String>) -> Self {
+        let spent_at = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |time| time.as_secs());
+        SpentNullifier { player, score, proof: proof.into(), spent_at }
+    }
+}
+
+#[derive(Debug)]
+pub enum NullifierError {
+    /// A proof for this game was already accepted
+    AlreadySubmitted { nullifier: B256, spent: SpentNullifier },
+    /// The registry could not be read or written
+    Storage(rusqlite::Error),
+}
+
+impl fmt::Display for NullifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NullifierError::AlreadySubmitted { nullifier, spent } => write!(
+                f,
+                "game already submitted: nullifier {} was spent at {} by {} (score {}, proof {})",
+                nullifier, spent.spent_at, spent.player, spent.score, spent.proof
+            ),
+            NullifierError::Storage(err) => write!(f, "nullifier registry error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for NullifierError {}
+
+impl From<rusqlite::Error> for NullifierError {
+    fn from(err: rusqlite::Error) -> Self {
+        NullifierError::Storage(err)
+    }
+}
+
+/// Set of nullifiers whose proof was accepted.
+pub struct NullifierRegistry {
+    db: Connection,
+}
+
+impl NullifierRegistry {
+    /// Registry at `$ZKLABUBU_NULLIFIER_DB`, or `nullifiers.db` if unset.
+    pub fn from_env() -> Result<Self, NullifierError> {
+        let path = std::env::var(NULLIFIER_DB_ENV).unwrap_or_else(|_| DEFAULT_NULLIFIER_DB.to_string());
+        Self::open(path)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, NullifierError> {
+        let db = Connection::open(path)?;
+        // Another process (prover or backend) may hold the write lock for a moment
+        db.busy_timeout(std::time::Duration::from_secs(5))?;
+        db.execute_batch(
+            "CREATE TABLE IF NOT EXISTS nullifiers (
+                nullifier TEXT PRIMARY KEY,
+                player TEXT NOT NULL,
+                score INTEGER NOT NULL,
+                proof TEXT NOT NULL,
+                spent_at INTEGER NOT NULL
+            )",
+        )?;
+        Ok(NullifierRegistry { db })
+    }
+
+    /// The proof that spent `nullifier`, if any.
+    pub fn get(&self, nullifier: B256) -> Result<Option<SpentNullifier>, NullifierError> {
+        let spent = self
+            .db
+            .query_row(
+                "SELECT player, score, proof, spent_at FROM nullifiers WHERE nullifier = ?1",
+                params![nullifier.to_string()],
+                |row| {
+                    let player: String = row.get(0)?;
+                    Ok(SpentNullifier {
+                        player: player.parse().unwrap_or(Address::ZERO),
+                        score: row.get(1)?,
+                        proof: row.get(2)?,
+                        spent_at: row.get(3)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(spent)
+    }
+
+    /// Fails with [`NullifierError::AlreadySubmitted`] if `nullifier` was spent.
+    pub fn check(&self, nullifier: B256) -> Result<(), NullifierError> {
+        if nullifier.is_zero() {
+            return Ok(());
+        }
+        match self.get(nullifier)? {
+            Some(spent) => Err(NullifierError::AlreadySubmitted { nullifier, spent }),
+            None => Ok(()),
+        }
+    }
+
+    /// Record `nullifier` as spent by `spent`.
+    ///
+    /// The insert is atomic: of two provers racing for the same game only one
+    /// succeeds, the other gets [`NullifierError::AlreadySubmitted`].
+    pub fn spend(&self, nullifier: B256, spent: &SpentNullifier) -> Result<(), NullifierError> {
+        if nullifier.is_zero() {
+            return Ok(());
+        }
+        let inserted = self.db.execute(
+            "INSERT OR IGNORE INTO nullifiers (nullifier, player, score, proof, spent_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![nullifier.to_string(), spent.player.to_string(), spent.score, spent.proof, spent.spent_at],
+        )?;
+        if inserted == 0 {
+            return self.check(nullifier);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spend_rejects_a_second_proof_of_the_same_game() {
+        let registry = NullifierRegistry::open(":memory:").unwrap();
+        let nullifier = B256::repeat_byte(1);
+        let first = SpentNullifier::new(Address::repeat_byte(0xaa), 120, "proofs/first.bin");
+
+        registry.check(nullifier).unwrap();
+        registry.spend(nullifier, &first).unwrap();
+        assert_eq!(registry.get(nullifier).unwrap(), Some(first.clone()));
+
+        let second = SpentNullifier::new(Address::repeat_byte(0xbb), 500, "proofs/second.bin");
+        match registry.spend(nullifier, &second) {
+            Err(NullifierError::AlreadySubmitted { nullifier: spent_nullifier, spent }) => {
+                assert_eq!(spent_nullifier, nullifier);
+                assert_eq!(spent, first);
+            }
+            other => panic!("expected AlreadySubmitted, got {:?}", other),
+        }
+        assert!(matches!(registry.check(nullifier), Err(NullifierError::AlreadySubmitted { .. })));
+        // The first proof stays recorded
+        assert_eq!(registry.get(nullifier).unwrap(), Some(first));
+
+        // Other games are unaffected
+        registry.check(B256::repeat_byte(2)).unwrap();
+    }
+
+    #[test]
+    fn zero_nullifier_is_never_recorded() {
+        let registry = NullifierRegistry::open(":memory:").unwrap();
+        let spent = SpentNullifier::new(Address::ZERO, 10, "proofs/untracked.bin");
+        registry.spend(B256::ZERO, &spent).unwrap();
+        registry.spend(B256::ZERO, &spent).unwrap();
+        registry.check(B256::ZERO).unwrap();
+        assert_eq!(registry.get(B256::ZERO).unwrap(), None);
+    }
+}
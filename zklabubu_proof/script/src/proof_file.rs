@@ -0,0 +1,93 @@
+//! Proof files owned by one prover run.
+//!
+//! Several provers can run at once (the backend starts one per request). A run
+//! reserves its proof path by creating the file before proving, so no other run
+//! writes to it. If the proof is not recorded (e.g. the nullifier was spent by
+//! another run first), the run removes the file it created, and only that file.
+
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use alloy_primitives::B256;
+
+/// Default proof path of a `prove` run, unique per run.
+pub fn default_proof_path() -> PathBuf {
+    PathBuf::from(format!("zklabubuio_game_proof-{}.bin", hex::encode(&B256::random()[..8])))
+}
+
+/// A proof path reserved by this run, removed on drop unless kept.
+#[derive(Debug)]
+pub struct ProofFile {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl ProofFile {
+    /// Create the (empty) file, failing if it already exists.
+    pub fn reserve(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        OpenOptions::new().write(true).create_new(true).open(&path)?;
+        Ok(ProofFile { path, keep: false })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Keep the file once the proof is recorded.
+    pub fn keep(mut self) -> PathBuf {
+        self.keep = true;
+        self.path.clone()
+    }
+}
+
+impl Drop for ProofFile {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("zklabubu-proof-file-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn dropping_removes_only_the_reserved_file() {
+        let path = temp_path("dropped.bin");
+        let file = ProofFile::reserve(&path).unwrap();
+        assert!(path.exists());
+
+        // Another run can't claim the same path, and failing doesn't touch the file
+        assert_eq!(ProofFile::reserve(&path).unwrap_err().kind(), io::ErrorKind::AlreadyExists);
+        assert!(path.exists());
+
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn kept_file_stays() {
+        let path = temp_path("kept.bin");
+        let file = ProofFile::reserve(&path).unwrap();
+        fs::write(file.path(), b"proof").unwrap();
+
+        assert_eq!(file.keep(), path);
+        assert_eq!(fs::read(&path).unwrap(), b"proof");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn default_paths_are_unique() {
+        assert_ne!(default_proof_path(), default_proof_path());
+    }
+}